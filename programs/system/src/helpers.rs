@@ -0,0 +1,287 @@
+use core::cmp::Ordering;
+
+use pinocchio::{
+    account_info::AccountInfo,
+    instruction::Signer,
+    program_error::ProgramError,
+    sysvars::{rent::Rent, Sysvar},
+    ProgramResult,
+};
+
+use crate::instructions::Transfer;
+
+/// Resize an account and adjust its lamports to keep it exactly rent-exempt.
+///
+/// When the account requires more lamports for its new length, the difference is
+/// transferred from the payer using the System program. When the account has more
+/// lamports than required, the surplus is moved to the destination account.
+///
+/// ### Accounts:
+///   0. `[WRITE]` Account to resize, owned by the invoking program
+///   1. `[WRITE, SIGNER]` Payer account
+///   2. `[WRITE]` Destination account for the refunded lamports (can be the payer,
+///      but not the account to resize)
+///   3. `[]` System program
+///
+/// Returns [`ProgramError::InvalidArgument`] when the destination account is the
+/// account to resize.
+pub struct ResizeRentExempt<'a> {
+    /// Account to resize.
+    pub account: &'a AccountInfo,
+
+    /// Funding account for the rent top-up.
+    pub payer: &'a AccountInfo,
+
+    /// Account receiving the refunded lamports.
+    pub destination: &'a AccountInfo,
+
+    /// New data length of the account.
+    pub new_len: usize,
+}
+
+impl ResizeRentExempt<'_> {
+    #[inline(always)]
+    pub fn invoke(&self) -> ProgramResult {
+        self.invoke_signed(&[])
+    }
+
+    #[inline(always)]
+    pub fn invoke_signed(&self, signers: &[Signer]) -> ProgramResult {
+        self.invoke_signed_with_rent(&Rent::get()?, signers)
+    }
+
+    /// Resize the account using the provided rent parameters.
+    #[inline(always)]
+    fn invoke_signed_with_rent(&self, rent: &Rent, signers: &[Signer]) -> ProgramResult {
+        // The surplus lamports cannot be refunded to the account itself.
+        if self.destination.key() == self.account.key() {
+            return Err(ProgramError::InvalidArgument);
+        }
+
+        self.account.resize(self.new_len)?;
+
+        let required = rent.minimum_balance(self.new_len);
+        let current = self.account.lamports();
+
+        match required.cmp(&current) {
+            Ordering::Greater => Transfer {
+                from: self.payer,
+                to: self.account,
+                lamports: required - current,
+            }
+            .invoke_signed(signers),
+            Ordering::Less => {
+                let surplus = current - required;
+
+                let mut destination_lamports = self.destination.try_borrow_mut_lamports()?;
+                *destination_lamports = destination_lamports
+                    .checked_add(surplus)
+                    .ok_or(ProgramError::ArithmeticOverflow)?;
+
+                *self.account.try_borrow_mut_lamports()? = required;
+
+                Ok(())
+            }
+            Ordering::Equal => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use core::mem::{size_of, MaybeUninit};
+    use std::vec::Vec;
+
+    use pinocchio::{account_info::MAX_PERMITTED_DATA_INCREASE, entrypoint::deserialize};
+
+    use super::*;
+
+    /// Rent parameters with the default exemption threshold.
+    const RENT: Rent = Rent {
+        lamports_per_byte_year: 1,
+        exemption_threshold: 2.0,
+        burn_percent: 50,
+    };
+
+    /// Length of the account header in the input buffer: the borrow state and
+    /// flags, key, owner, lamports and data length.
+    const ACCOUNT_HEADER_LEN: usize = 88;
+
+    /// Creates an input buffer with non-duplicated accounts, as serialized by the
+    /// SVM loader.
+    ///
+    /// Each account is described by its lamports and data length, and has a key
+    /// filled with its index plus one. The account data is filled with `0xff`
+    /// bytes, including the space reserved for resizing.
+    fn create_input(accounts: &[(u64, usize)]) -> Vec<u64> {
+        let mut input = Vec::new();
+        input.extend_from_slice(&(accounts.len() as u64).to_le_bytes());
+
+        for (i, (lamports, data_len)) in accounts.iter().enumerate() {
+            let mut header = [0u8; ACCOUNT_HEADER_LEN];
+            // Non-duplicated marker.
+            header[0] = u8::MAX;
+            header[8..40].fill(i as u8 + 1);
+            header[72..80].copy_from_slice(&lamports.to_le_bytes());
+            header[80..88].copy_from_slice(&(*data_len as u64).to_le_bytes());
+            input.extend_from_slice(&header);
+            // Account data and space reserved for resizing, aligned to 8 bytes.
+            let data_len = (data_len + MAX_PERMITTED_DATA_INCREASE + 7) & !7;
+            input.resize(input.len() + data_len, 0xff);
+            // Rent epoch.
+            input.extend_from_slice(&0u64.to_le_bytes());
+        }
+
+        // Instruction data length and program id.
+        input.extend_from_slice(&0u64.to_le_bytes());
+        input.extend_from_slice(&[0; 32]);
+
+        input
+            .chunks(size_of::<u64>())
+            .map(|chunk| {
+                let mut word = [0u8; 8];
+                word[..chunk.len()].copy_from_slice(chunk);
+                u64::from_ne_bytes(word)
+            })
+            .collect()
+    }
+
+    /// Deserializes the accounts of the input buffer.
+    fn accounts<const N: usize>(input: &mut [u64]) -> [AccountInfo; N] {
+        let mut accounts = [const { MaybeUninit::<AccountInfo>::uninit() }; N];
+        // SAFETY: The input buffer is serialized as the SVM loader does.
+        let (_, count, _) = unsafe { deserialize(input.as_mut_ptr() as *mut u8, &mut accounts) };
+        assert_eq!(count, N);
+        // SAFETY: All `N` accounts have been initialized.
+        accounts.map(|account| unsafe { account.assume_init() })
+    }
+
+    #[test]
+    fn test_resize_rent_exempt_shrink() {
+        let mut input = create_input(&[(RENT.minimum_balance(100), 100), (1_000, 0), (10, 0)]);
+        let [account, payer, destination] = accounts(&mut input);
+
+        ResizeRentExempt {
+            account: &account,
+            payer: &payer,
+            destination: &destination,
+            new_len: 40,
+        }
+        .invoke_signed_with_rent(&RENT, &[])
+        .unwrap();
+
+        // The surplus is moved to the destination, not the payer.
+        assert_eq!(account.data_len(), 40);
+        assert_eq!(account.lamports(), RENT.minimum_balance(40));
+        assert_eq!(payer.lamports(), 1_000);
+        assert_eq!(
+            destination.lamports(),
+            10 + RENT.minimum_balance(100) - RENT.minimum_balance(40)
+        );
+
+        // The destination can be the payer.
+
+        ResizeRentExempt {
+            account: &account,
+            payer: &payer,
+            destination: &payer,
+            new_len: 0,
+        }
+        .invoke_signed_with_rent(&RENT, &[])
+        .unwrap();
+
+        assert_eq!(account.data_len(), 0);
+        assert_eq!(account.lamports(), RENT.minimum_balance(0));
+        assert_eq!(
+            payer.lamports(),
+            1_000 + RENT.minimum_balance(40) - RENT.minimum_balance(0)
+        );
+    }
+
+    #[test]
+    fn test_resize_rent_exempt_destination_is_account() {
+        let mut input = create_input(&[(RENT.minimum_balance(100), 100), (1_000, 0)]);
+        let [account, payer] = accounts(&mut input);
+
+        assert_eq!(
+            ResizeRentExempt {
+                account: &account,
+                payer: &payer,
+                destination: &account,
+                new_len: 40,
+            }
+            .invoke_signed_with_rent(&RENT, &[]),
+            Err(ProgramError::InvalidArgument)
+        );
+
+        // The account is not modified.
+        assert_eq!(account.data_len(), 100);
+        assert_eq!(account.lamports(), RENT.minimum_balance(100));
+    }
+
+    #[test]
+    fn test_resize_rent_exempt_grow() {
+        let mut input = create_input(&[(RENT.minimum_balance(10), 10), (1_000, 0), (10, 0)]);
+        let [account, payer, destination] = accounts(&mut input);
+
+        ResizeRentExempt {
+            account: &account,
+            payer: &payer,
+            destination: &destination,
+            new_len: 100,
+        }
+        .invoke_signed_with_rent(&RENT, &[])
+        .unwrap();
+
+        // The data is zero extended and the destination does not receive lamports.
+        assert_eq!(account.data_len(), 100);
+        assert!(account.try_borrow_data().unwrap()[..10]
+            .iter()
+            .all(|byte| *byte == 0xff));
+        assert!(account.try_borrow_data().unwrap()[10..]
+            .iter()
+            .all(|byte| *byte == 0));
+        assert_eq!(destination.lamports(), 10);
+
+        // The top-up is transferred from the payer with a CPI to the System
+        // program, which fails when the payer lamports are borrowed.
+
+        let borrowed = payer.try_borrow_mut_lamports().unwrap();
+
+        assert_eq!(
+            ResizeRentExempt {
+                account: &account,
+                payer: &payer,
+                destination: &destination,
+                new_len: 200,
+            }
+            .invoke_signed_with_rent(&RENT, &[]),
+            Err(ProgramError::AccountBorrowFailed)
+        );
+
+        drop(borrowed);
+    }
+
+    #[test]
+    fn test_resize_rent_exempt_exact_balance() {
+        // The account already holds the minimum balance of the new length.
+        let mut input = create_input(&[(RENT.minimum_balance(50), 100), (1_000, 0), (10, 0)]);
+        let [account, payer, destination] = accounts(&mut input);
+
+        ResizeRentExempt {
+            account: &account,
+            payer: &payer,
+            destination: &destination,
+            new_len: 50,
+        }
+        .invoke_signed_with_rent(&RENT, &[])
+        .unwrap();
+
+        assert_eq!(account.data_len(), 50);
+        assert_eq!(account.lamports(), RENT.minimum_balance(50));
+        assert_eq!(payer.lamports(), 1_000);
+        assert_eq!(destination.lamports(), 10);
+    }
+}
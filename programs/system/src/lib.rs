@@ -1,5 +1,6 @@
 #![no_std]
 
+pub mod helpers;
 pub mod instructions;
 
 pinocchio_pubkey::declare_id!("11111111111111111111111111111111");
@@ -0,0 +1,143 @@
+//! Log levels filtered at compile time.
+//!
+//! The maximum enabled level is selected with one of the `max_level_*` crate
+//! features (`max_level_off`, `max_level_error`, `max_level_warn`, `max_level_info`,
+//! `max_level_debug` and `max_level_trace`). When more than one of the features is
+//! enabled, the most restrictive one is used. All levels are enabled by default.
+//!
+//! The levels are the same as the ones of the leveled `msg!` of `pinocchio`. With
+//! the optional `pinocchio` feature, the `max_level_*` features are also enabled on
+//! the `pinocchio` crate, so a single feature selects the maximum level of both.
+//!
+//! The leveled macros ([`error!`](crate::error!), [`warn!`](crate::warn!),
+//! [`info!`](crate::info!), [`debug!`](crate::debug!) and [`trace!`](crate::trace!))
+//! check the level at compile time, so messages of disabled levels do not consume
+//! any compute units.
+
+/// Log levels, ordered from the least to the most verbose.
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    /// Errors that prevent the program from completing an operation.
+    Error = 1,
+
+    /// Unexpected conditions that do not prevent the program from completing
+    /// an operation.
+    Warn,
+
+    /// Information about the execution of the program.
+    Info,
+
+    /// Detailed information useful when debugging the program.
+    Debug,
+
+    /// Very detailed information about the execution of the program.
+    Trace,
+}
+
+impl Level {
+    /// Check whether the level is enabled by the [`MAX_LEVEL`] or not.
+    #[inline(always)]
+    pub const fn is_enabled(self) -> bool {
+        match MAX_LEVEL {
+            Some(max_level) => self as u8 <= max_level as u8,
+            None => false,
+        }
+    }
+}
+
+/// Maximum log level enabled at compile time.
+///
+/// The value is `None` when logging is disabled by the `max_level_off` feature.
+pub const MAX_LEVEL: Option<Level> = if cfg!(feature = "max_level_off") {
+    None
+} else if cfg!(feature = "max_level_error") {
+    Some(Level::Error)
+} else if cfg!(feature = "max_level_warn") {
+    Some(Level::Warn)
+} else if cfg!(feature = "max_level_info") {
+    Some(Level::Info)
+} else if cfg!(feature = "max_level_debug") {
+    Some(Level::Debug)
+} else {
+    Some(Level::Trace)
+};
+
+/// Log a message at the specified level.
+///
+/// The message is only logged when the level is enabled; otherwise, the call is
+/// removed at compile time and the arguments are not evaluated.
+#[doc(hidden)]
+#[cfg(feature = "macro")]
+#[macro_export]
+macro_rules! __log_with_level {
+    ( $level:ident, $( $arg:tt )+ ) => {
+        if const { $crate::level::Level::$level.is_enabled() } {
+            $crate::log!($( $arg )+)
+        }
+    };
+}
+
+/// Log a message at the [`Level::Error`] level.
+///
+/// The arguments are the same as the [`log!`](crate::log!) macro.
+///
+/// # Example
+///
+/// ```
+/// use pinocchio_log::error;
+///
+/// let balance = 10u64;
+/// error!("insufficient funds: {}", balance);
+/// ```
+#[cfg(feature = "macro")]
+#[macro_export]
+macro_rules! error {
+    ( $( $arg:tt )+ ) => {
+        $crate::__log_with_level!(Error, $( $arg )+)
+    };
+}
+
+/// Log a message at the [`Level::Warn`] level.
+///
+/// The arguments are the same as the [`log!`](crate::log!) macro.
+#[cfg(feature = "macro")]
+#[macro_export]
+macro_rules! warn {
+    ( $( $arg:tt )+ ) => {
+        $crate::__log_with_level!(Warn, $( $arg )+)
+    };
+}
+
+/// Log a message at the [`Level::Info`] level.
+///
+/// The arguments are the same as the [`log!`](crate::log!) macro.
+#[cfg(feature = "macro")]
+#[macro_export]
+macro_rules! info {
+    ( $( $arg:tt )+ ) => {
+        $crate::__log_with_level!(Info, $( $arg )+)
+    };
+}
+
+/// Log a message at the [`Level::Debug`] level.
+///
+/// The arguments are the same as the [`log!`](crate::log!) macro.
+#[cfg(feature = "macro")]
+#[macro_export]
+macro_rules! debug {
+    ( $( $arg:tt )+ ) => {
+        $crate::__log_with_level!(Debug, $( $arg )+)
+    };
+}
+
+/// Log a message at the [`Level::Trace`] level.
+///
+/// The arguments are the same as the [`log!`](crate::log!) macro.
+#[cfg(feature = "macro")]
+#[macro_export]
+macro_rules! trace {
+    ( $( $arg:tt )+ ) => {
+        $crate::__log_with_level!(Trace, $( $arg )+)
+    };
+}
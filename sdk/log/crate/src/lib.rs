@@ -42,17 +42,136 @@
 //! logger.append_with_args(lamports, &[Argument::Precision(9)]);
 //! logger.log();
 //! ```
+//!
+//! Custom types can implement the `Log` trait using the `Log` derive macro:
+//!
+//! ```
+//! use pinocchio_log::{logger::Logger, Log};
+//!
+//! #[derive(Log)]
+//! struct Transfer<'a> {
+//!     #[log(precision = 9)]
+//!     lamports: u64,
+//!     memo: &'a str,
+//!     #[log(skip)]
+//!     bump: u8,
+//! }
+//!
+//! #[derive(Log)]
+//! enum Status {
+//!     Pending,
+//!     Completed { slot: u64 },
+//! }
+//!
+//! let transfer = Transfer { lamports: 1_500_000_000, memo: "rent", bump: 255 };
+//!
+//! let mut logger = Logger::<100>::default();
+//! logger.append(transfer);
+//! assert!(&*logger == r#"Transfer { lamports: 1.500000000, memo: "rent", .. }"#.as_bytes());
+//!
+//! logger.clear();
+//! logger.append(Status::Completed { slot: 1 });
+//! assert!(&*logger == "Completed".as_bytes());
+//! ```
+//!
+//! Messages can also be formatted into a caller provided buffer without being logged,
+//! using the `format_into!` macro:
+//!
+//! ```
+//! use core::mem::MaybeUninit;
+//! use pinocchio_log::format_into;
+//!
+//! let mut buffer = [MaybeUninit::<u8>::uninit(); 32];
+//! let index = 7u16;
+//!
+//! let seed = format_into!(buffer, "vault-{index}-{:.2}", 150u64);
+//! assert_eq!(seed, b"vault-7-1.50");
+//! ```
+//!
+//! The leveled macros (`error!`, `warn!`, `info!`, `debug!` and `trace!`) log messages
+//! only when their level is enabled by the `max_level_*` crate features, removing the
+//! calls of disabled levels at compile time:
+//!
+//! ```
+//! use pinocchio_log::{debug, info};
+//!
+//! let amount = 1_000_000_000u64;
+//!
+//! info!("transfer amount: {}", amount);
+//! // Removed when the maximum level is `info` or lower.
+//! debug!("transfer amount (SOL): {:.9}", amount);
+//! ```
 
 #![no_std]
 
+pub mod level;
 pub mod logger;
 
 #[cfg(feature = "macro")]
 pub use pinocchio_log_macro::*;
 
+// Allows the macros to be used in the tests, since they expand to `pinocchio_log` paths.
+#[cfg(all(test, feature = "macro"))]
+extern crate self as pinocchio_log;
+
+/// Invalid uses of the formatting macros that must fail to compile.
+///
+/// Buffers of initialized bytes, which the formatter could leave uninitialized:
+///
+/// ```compile_fail
+/// let mut buffer = [0u8; 16];
+/// pinocchio_log::format_into!(buffer, "{}", 1u8);
+/// ```
+///
+/// Arguments that are not referenced by the format string:
+///
+/// ```compile_fail
+/// let mut buffer = [core::mem::MaybeUninit::<u8>::uninit(); 16];
+/// pinocchio_log::format_into!(buffer, "{0}", 1u8, 2u8);
+/// ```
+///
+/// ```compile_fail
+/// let mut buffer = [core::mem::MaybeUninit::<u8>::uninit(); 16];
+/// pinocchio_log::format_into!(buffer, "{}", 1u8, x = 2u8);
+/// ```
+///
+/// Indexes out of range of the positional arguments:
+///
+/// ```compile_fail
+/// let mut buffer = [core::mem::MaybeUninit::<u8>::uninit(); 16];
+/// pinocchio_log::format_into!(buffer, "{1}", 1u8);
+/// ```
+///
+/// Positional arguments after named arguments:
+///
+/// ```compile_fail
+/// let mut buffer = [core::mem::MaybeUninit::<u8>::uninit(); 16];
+/// pinocchio_log::format_into!(buffer, "{x}{}", x = 1u8, 2u8);
+/// ```
+///
+/// Hexadecimal specifiers with arguments that are not integers or byte arrays, and
+/// the alternate flag with byte arrays:
+///
+/// ```compile_fail
+/// let mut buffer = [core::mem::MaybeUninit::<u8>::uninit(); 16];
+/// pinocchio_log::format_into!(buffer, "{:x}", "a");
+/// ```
+///
+/// ```compile_fail
+/// let mut buffer = [core::mem::MaybeUninit::<u8>::uninit(); 16];
+/// pinocchio_log::format_into!(buffer, "{:#x}", [1u8, 2]);
+/// ```
+#[cfg(all(doctest, feature = "macro"))]
+pub struct InvalidFormatTests;
+
 #[cfg(test)]
 mod tests {
-    use crate::logger::{Argument, Logger};
+    use core::mem::MaybeUninit;
+
+    use crate::{
+        level::{Level, MAX_LEVEL},
+        logger::{max_len, Argument, Formatter, Log, Logger, MultilineLogger},
+    };
 
     /// Helper macro to generate test cases for numeric types.
     ///
@@ -437,4 +556,524 @@ mod tests {
 
         assert!(&*logger == "fal@".as_bytes());
     }
+
+    #[cfg(feature = "pubkey")]
+    #[test]
+    fn test_logger_pubkey() {
+        use pinocchio_pubkey::base58::Address;
+
+        let token_program = Address([
+            6, 221, 246, 225, 215, 101, 161, 147, 217, 203, 225, 70, 206, 235, 121, 172, 28, 180,
+            133, 237, 95, 91, 55, 145, 58, 140, 245, 133, 126, 255, 0, 169,
+        ]);
+
+        let mut logger = Logger::<50>::default();
+        logger.append("owner=").append(token_program);
+
+        assert!(&*logger == "owner=TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA".as_bytes());
+
+        let mut logger = Logger::<50>::default();
+        logger.append(Address([0; 32]));
+
+        assert!(&*logger == "11111111111111111111111111111111".as_bytes());
+
+        let mut logger = Logger::<10>::default();
+        logger.append(token_program);
+
+        assert!(&*logger == "TokenkegQ@".as_bytes());
+    }
+
+    #[test]
+    fn test_logger_bytes() {
+        // Byte arrays are written as a list of values, by value or by reference.
+        let mut logger = Logger::<20>::default();
+        logger.append([0x01u8, 0xab, 0xff]);
+
+        assert!(&*logger == "[1, 171, 255]".as_bytes());
+
+        let bytes: &[u8; 3] = &[1, 2, 3];
+
+        let mut logger = Logger::<20>::default();
+        logger.append(bytes);
+
+        assert!(&*logger == "[1, 2, 3]".as_bytes());
+
+        // The hex arguments write the bytes in hexadecimal.
+        let mut logger = Logger::<20>::default();
+        logger.append_with_args([0x01u8, 0xab, 0xff], &[Argument::LowerHex]);
+
+        assert!(&*logger == "01abff".as_bytes());
+
+        let mut logger = Logger::<20>::default();
+        logger.append_with_args(bytes, &[Argument::LowerHex]);
+
+        assert!(&*logger == "010203".as_bytes());
+
+        let mut logger = Logger::<5>::default();
+        logger.append_with_args([0x01u8, 0xab, 0xff], &[Argument::LowerHex]);
+
+        assert!(&*logger == "01ab@".as_bytes());
+
+        let mut logger = Logger::<20>::default();
+        logger.append_with_args([0x01u8, 0xab, 0xff], &[Argument::UpperHex]);
+
+        assert!(&*logger == "01ABFF".as_bytes());
+
+        let mut logger = Logger::<20>::default();
+        logger.append_with_args(
+            [0x01u8, 0xab, 0xff, 0x10],
+            &[Argument::LowerHex, Argument::TruncateEnd(6)],
+        );
+
+        assert!(&*logger == "01a...".as_bytes());
+
+        let mut logger = Logger::<20>::default();
+        logger.append_with_args(
+            [0x01u8, 0xab, 0xff, 0x10],
+            &[Argument::LowerHex, Argument::TruncateStart(6)],
+        );
+
+        assert!(&*logger == "...f10".as_bytes());
+
+        let mut logger = Logger::<20>::default();
+        logger.append_with_args(
+            [0x01u8, 0xab],
+            &[Argument::LowerHex, Argument::TruncateEnd(6)],
+        );
+
+        assert!(&*logger == "01ab".as_bytes());
+    }
+
+    #[test]
+    fn test_logger_pubkey_bytes() {
+        // Addresses are written as a list of values, unless wrapped in an `Address`.
+        let system_program: &[u8; 32] = &[0; 32];
+
+        let mut logger = Logger::<200>::default();
+        logger.append(system_program);
+
+        assert!(&*logger == "[0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]".as_bytes());
+    }
+
+    #[test]
+    fn test_logger_option() {
+        let mut logger = Logger::<20>::default();
+        logger.append(Some(42u64)).append(" ").append(None::<u64>);
+
+        assert!(&*logger == "42 None".as_bytes());
+
+        let mut logger = Logger::<20>::default();
+        logger.append_with_args(Some(1_500u64), &[Argument::Precision(3)]);
+
+        assert!(&*logger == "1.500".as_bytes());
+
+        let mut logger = Logger::<3>::default();
+        logger.append(None::<u64>);
+
+        assert!(&*logger == "No@".as_bytes());
+    }
+
+    #[test]
+    fn test_logger_char() {
+        let mut logger = Logger::<20>::default();
+        logger.append('a').append('é').append('🦀');
+
+        assert!(&*logger == "aé🦀".as_bytes());
+    }
+
+    #[test]
+    fn test_logger_decimal() {
+        use crate::logger::Decimal;
+
+        let mut logger = Logger::<20>::default();
+        logger.append(Decimal::new(1_500_000u64, 6));
+
+        assert!(&*logger == "1.500000".as_bytes());
+
+        let mut logger = Logger::<20>::default();
+        logger.append(Decimal::new(-25i32, 3));
+
+        assert!(&*logger == "-0.025".as_bytes());
+
+        let mut logger = Logger::<20>::default();
+        logger.append(Decimal::new(7u8, 0));
+
+        assert!(&*logger == "7".as_bytes());
+
+        let mut logger = Logger::<20>::default();
+        logger.append_with_args(Decimal::new(1_500u64, 3), &[Argument::SignPlus]);
+
+        assert!(&*logger == "+1.500".as_bytes());
+    }
+
+    #[test]
+    fn test_logger_hex() {
+        let mut logger = Logger::<20>::default();
+
+        logger.append_with_args(255u8, &[Argument::LowerHex]);
+        assert!(&*logger == "ff".as_bytes());
+
+        logger.clear();
+
+        logger.append_with_args(0xabcdu32, &[Argument::UpperHex, Argument::Alternate]);
+        assert!(&*logger == "0xABCD".as_bytes());
+
+        logger.clear();
+
+        logger.append_with_args(0u64, &[Argument::LowerHex, Argument::Alternate]);
+        assert!(&*logger == "0x0".as_bytes());
+
+        logger.clear();
+
+        logger.append_with_args(-1i16, &[Argument::LowerHex]);
+        assert!(&*logger == "ffff".as_bytes());
+
+        let mut logger = Logger::<5>::default();
+
+        logger.append_with_args(u64::MAX, &[Argument::LowerHex, Argument::Alternate]);
+        assert!(&*logger == "0xff@".as_bytes());
+    }
+
+    #[test]
+    fn test_logger_sign() {
+        let mut logger = Logger::<20>::default();
+
+        logger.append_with_args(5u64, &[Argument::SignPlus]);
+        assert!(&*logger == "+5".as_bytes());
+
+        logger.clear();
+
+        logger.append_with_args(0i32, &[Argument::SignPlus]);
+        assert!(&*logger == "+0".as_bytes());
+
+        logger.clear();
+
+        logger.append_with_args(-5i32, &[Argument::SignPlus]);
+        assert!(&*logger == "-5".as_bytes());
+
+        logger.clear();
+
+        logger.append_with_args(1_500i64, &[Argument::SignPlus, Argument::Precision(3)]);
+        assert!(&*logger == "+1.500".as_bytes());
+    }
+
+    #[test]
+    fn test_logger_exponent() {
+        let mut logger = Logger::<20>::default();
+
+        logger.append_with_args(1_500_000_000u64, &[Argument::LowerExp]);
+        assert!(&*logger == "1.5e9".as_bytes());
+
+        logger.clear();
+
+        logger.append_with_args(0u8, &[Argument::LowerExp]);
+        assert!(&*logger == "0e0".as_bytes());
+
+        logger.clear();
+
+        logger.append_with_args(7u32, &[Argument::LowerExp, Argument::Precision(2)]);
+        assert!(&*logger == "7.00e0".as_bytes());
+
+        logger.clear();
+
+        logger.append_with_args(-123_456i64, &[Argument::LowerExp, Argument::Precision(2)]);
+        assert!(&*logger == "-1.23e5".as_bytes());
+
+        logger.clear();
+
+        logger.append_with_args(u128::MAX, &[Argument::LowerExp, Argument::Precision(1)]);
+        assert!(&*logger == "3.4e38".as_bytes());
+
+        let mut logger = Logger::<4>::default();
+
+        logger.append_with_args(1_234u64, &[Argument::LowerExp]);
+        assert!(&*logger == "1.2@".as_bytes());
+    }
+
+    #[test]
+    fn test_logger_width() {
+        use crate::logger::Alignment;
+
+        let mut logger = Logger::<20>::default();
+
+        logger.append_with_args(42u64, &[Argument::Width(5)]);
+        logger.append("|");
+        logger.append_with_args("ab", &[Argument::Width(4)]);
+        logger.append("|");
+        assert!(&*logger == "   42|ab  |".as_bytes());
+
+        logger.clear();
+
+        logger.append_with_args(
+            42u64,
+            &[Argument::Width(5), Argument::Align(Alignment::Left)],
+        );
+        logger.append("|");
+        logger.append_with_args(
+            "ab",
+            &[Argument::Width(4), Argument::Align(Alignment::Right)],
+        );
+        assert!(&*logger == "42   |  ab".as_bytes());
+
+        logger.clear();
+
+        // Width is measured in characters.
+        logger.append_with_args(
+            "é",
+            &[Argument::Width(3), Argument::Align(Alignment::Right)],
+        );
+        assert!(&*logger == "  é".as_bytes());
+
+        logger.clear();
+
+        // Values larger than the width are not truncated.
+        logger.append_with_args(123_456u64, &[Argument::Width(3)]);
+        assert!(&*logger == "123456".as_bytes());
+
+        let mut logger = Logger::<4>::default();
+
+        logger.append_with_args("ab", &[Argument::Width(6)]);
+        assert!(&*logger == "ab @".as_bytes());
+
+        let mut logger = Logger::<4>::default();
+
+        logger.append_with_args(42u64, &[Argument::Width(6)]);
+        assert!(&*logger == "   @".as_bytes());
+
+        // Width larger than the remaining space of the buffer.
+        let mut logger = Logger::<10>::default();
+
+        logger.append("abcdefgh");
+        logger.append_with_args(1u8, &[Argument::Width(20)]);
+        assert!(&*logger == "abcdefgh @".as_bytes());
+
+        let mut logger = Logger::<10>::default();
+
+        logger.append("abcdefgh");
+        logger.append_with_args("x", &[Argument::Width(20)]);
+        assert!(&*logger == "abcdefghx@".as_bytes());
+    }
+
+    #[test]
+    fn test_logger_zero_pad() {
+        let mut logger = Logger::<20>::default();
+
+        logger.append_with_args(42u64, &[Argument::Width(8), Argument::ZeroPad]);
+        assert!(&*logger == "00000042".as_bytes());
+
+        logger.clear();
+
+        logger.append_with_args(-42i32, &[Argument::Width(5), Argument::ZeroPad]);
+        assert!(&*logger == "-0042".as_bytes());
+
+        logger.clear();
+
+        logger.append_with_args(
+            255u8,
+            &[
+                Argument::LowerHex,
+                Argument::Alternate,
+                Argument::Width(6),
+                Argument::ZeroPad,
+            ],
+        );
+        assert!(&*logger == "0x00ff".as_bytes());
+
+        logger.clear();
+
+        logger.append_with_args(
+            7u8,
+            &[Argument::SignPlus, Argument::Width(4), Argument::ZeroPad],
+        );
+        assert!(&*logger == "+007".as_bytes());
+    }
+
+    #[test]
+    fn test_multiline_logger() {
+        let mut logger = MultilineLogger::<10>::default();
+
+        logger.append("abc").append(12345u32);
+        assert!(&*logger == "abc12345".as_bytes());
+
+        // The value does not fit in the remaining space, so the current
+        // line is logged and the value is written on a new line.
+        logger.append("defg");
+        assert!(&*logger == "defg".as_bytes());
+
+        // A value larger than the buffer is truncated.
+        logger.append("-0123456789");
+        assert!(&*logger == "-01234567@".as_bytes());
+
+        // A full buffer is logged before appending.
+        logger.append_with_args(5u8, &[Argument::Width(3)]);
+        assert!(&*logger == "  5".as_bytes());
+
+        // Values that exactly fill the remaining space stay on the same line.
+        logger.append("6789").append(123u8);
+        assert!(&*logger == "  56789123".as_bytes());
+
+        logger.append("abcdefghij");
+        assert!(&*logger == "abcdefghij".as_bytes());
+
+        logger.log();
+        assert!(logger.is_empty());
+    }
+
+    #[cfg(feature = "macro")]
+    #[test]
+    fn test_log_multiline() {
+        // The message does not fit in a single line of the buffer, so it is logged
+        // as "values=" and "18446744073709551615 1 end".
+        crate::log!(multiline = 24, "values={} {} {}", u64::MAX, 1u8, "end");
+        crate::log!(multiline = 24, "no values");
+    }
+
+    #[test]
+    fn test_formatter() {
+        let mut buffer = [MaybeUninit::<u8>::uninit(); 12];
+        let mut formatter = Formatter::new(&mut buffer);
+
+        formatter
+            .append("seed-")
+            .append_with_args(42u8, &[Argument::Width(3), Argument::ZeroPad]);
+        assert!(&*formatter == "seed-042".as_bytes());
+        assert_eq!(formatter.remaining(), 4);
+
+        formatter.append(-12345i32);
+        assert!(formatter.is_full());
+        assert_eq!(formatter.finish(), "seed-042-12@".as_bytes());
+
+        let mut formatter = Formatter::new(&mut []);
+        formatter.append("empty");
+        assert!(formatter.finish().is_empty());
+
+        // Width larger than the remaining space of the buffer.
+        let mut buffer = [MaybeUninit::<u8>::uninit(); 10];
+        let mut formatter = Formatter::new(&mut buffer);
+
+        formatter
+            .append("abcdefgh")
+            .append_with_args(1u8, &[Argument::Width(20), Argument::ZeroPad]);
+        assert_eq!(formatter.finish(), "abcdefgh0@".as_bytes());
+    }
+
+    #[cfg(feature = "macro")]
+    #[test]
+    fn test_format_into_arguments() {
+        let mut buffer = [MaybeUninit::<u8>::uninit(); 32];
+
+        // Implicit arguments.
+        assert_eq!(crate::format_into!(buffer, "{}-{}", 1u8, "a"), b"1-a");
+
+        // Explicit indexes, reusing arguments and mixed with implicit ones.
+        assert_eq!(crate::format_into!(buffer, "{0}{1}{0}", 7u8, 8u8), b"787");
+        assert_eq!(crate::format_into!(buffer, "{1}{}{}", 7u8, 8u8), b"878");
+
+        // Named arguments, evaluated once.
+        let mut calls = 0;
+        let mut next = || {
+            calls += 1;
+            calls
+        };
+        assert_eq!(
+            crate::format_into!(buffer, "{x}+{y}={x:>3}", x = next(), y = 4u8),
+            b"1+4=  1"
+        );
+        assert_eq!(calls, 1);
+
+        // Captured identifiers.
+        let amount = 150u64;
+        assert_eq!(
+            crate::format_into!(buffer, "amount={amount:.2}"),
+            b"amount=1.50"
+        );
+
+        // Escaped braces.
+        assert_eq!(crate::format_into!(buffer, "{{{}}}", 5u8), b"{5}");
+        assert_eq!(crate::format_into!(buffer, "{{}}"), b"{}");
+    }
+
+    #[cfg(feature = "macro")]
+    #[test]
+    fn test_format_into_bytes() {
+        let mut buffer = [MaybeUninit::<u8>::uninit(); 32];
+        let bytes = [0x01u8, 0xab, 0xff];
+
+        assert_eq!(crate::format_into!(buffer, "{}", bytes), b"[1, 171, 255]");
+        assert_eq!(crate::format_into!(buffer, "{}", &bytes), b"[1, 171, 255]");
+        assert_eq!(crate::format_into!(buffer, "{:x}", bytes), b"01abff");
+        assert_eq!(crate::format_into!(buffer, "{:X}", &bytes), b"01ABFF");
+        assert_eq!(crate::format_into!(buffer, "{:8x}|", bytes), b"01abff  |");
+
+        // Integers are still written in hexadecimal.
+        assert_eq!(crate::format_into!(buffer, "{:x}", 255u8), b"ff");
+    }
+
+    #[test]
+    fn test_level() {
+        assert!(Level::Error < Level::Warn);
+        assert!(Level::Debug < Level::Trace);
+
+        // The most restrictive `max_level_*` feature selects the maximum level, and
+        // all levels are enabled when none of them is enabled.
+        let off = cfg!(feature = "max_level_off");
+        let error = off || cfg!(feature = "max_level_error");
+        let warn = error || cfg!(feature = "max_level_warn");
+        let info = warn || cfg!(feature = "max_level_info");
+        let debug = info || cfg!(feature = "max_level_debug");
+
+        assert_eq!(Level::Error.is_enabled(), !off);
+        assert_eq!(Level::Warn.is_enabled(), !error);
+        assert_eq!(Level::Info.is_enabled(), !warn);
+        assert_eq!(Level::Debug.is_enabled(), !info);
+        assert_eq!(Level::Trace.is_enabled(), !debug);
+
+        let max_level = if off {
+            None
+        } else if error {
+            Some(Level::Error)
+        } else if warn {
+            Some(Level::Warn)
+        } else if info {
+            Some(Level::Info)
+        } else if debug {
+            Some(Level::Debug)
+        } else {
+            Some(Level::Trace)
+        };
+        assert_eq!(MAX_LEVEL, max_level);
+    }
+
+    #[test]
+    fn test_max_len() {
+        assert_eq!(max_len(u64::MAX_LEN, &[]), 20);
+        assert_eq!(max_len(i8::MAX_LEN, &[]), 4);
+        assert_eq!(max_len(u64::MAX_LEN, &[Argument::Precision(9)]), 31);
+        assert_eq!(max_len(u8::MAX_LEN, &[Argument::Width(10)]), 10);
+        assert_eq!(max_len(<&str>::MAX_LEN, &[Argument::TruncateEnd(10)]), 10);
+        assert_eq!(<&[u16; 2]>::MAX_LEN, Some(16));
+        assert_eq!(<[u8; 4]>::MAX_LEN, Some(22));
+        assert_eq!(<Option<bool>>::MAX_LEN, Some(5));
+        assert_eq!(<&str>::MAX_LEN, None);
+    }
+
+    #[cfg(feature = "pubkey")]
+    #[test]
+    fn test_logger_address() {
+        use pinocchio_pubkey::base58::Address;
+
+        let address: Address = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"
+            .parse()
+            .unwrap();
+
+        let mut logger = Logger::<50>::default();
+        logger.append("owner=").append(address);
+
+        assert!(&*logger == "owner=TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA".as_bytes());
+
+        let mut logger = Logger::<50>::default();
+        logger.append_with_args(address, &[Argument::TruncateEnd(10)]);
+
+        assert!(&*logger == "Tokenke...".as_bytes());
+    }
 }
@@ -44,6 +44,15 @@ const TRUNCATED: u8 = b'@';
 /// An uninitialized byte.
 const UNINIT_BYTE: MaybeUninit<u8> = MaybeUninit::uninit();
 
+#[cfg(feature = "pubkey")]
+use pinocchio_pubkey::base58::{encode as encode_base58, MAX_BASE58_LEN};
+
+/// Hexadecimal digits.
+const HEX_DIGITS: &[u8; 16] = b"0123456789abcdef";
+
+/// Uppercase hexadecimal digits.
+const UPPER_HEX_DIGITS: &[u8; 16] = b"0123456789ABCDEF";
+
 /// Logger to efficiently format log messages.
 ///
 /// The logger is a fixed size buffer that can be used to format log messages
@@ -88,31 +97,16 @@ impl<const BUFFER: usize> Logger<BUFFER> {
     /// Append a value to the logger with formatting arguments.
     #[inline]
     pub fn append_with_args<T: Log>(&mut self, value: T, args: &[Argument]) -> &mut Self {
-        if self.is_full() {
-            if BUFFER > 0 {
-                // SAFETY: the buffer is checked to be full.
-                unsafe {
-                    let last = self.buffer.get_unchecked_mut(BUFFER - 1);
-                    last.write(TRUNCATED);
-                }
-            }
-        } else {
-            self.len += value.write_with_args(&mut self.buffer[self.len..], args);
-
-            if self.len > BUFFER {
-                // Indicates that the buffer is full.
-                self.len = BUFFER;
-                // SAFETY: the buffer length is checked to greater than `BUFFER`.
-                unsafe {
-                    let last = self.buffer.get_unchecked_mut(BUFFER - 1);
-                    last.write(TRUNCATED);
-                }
-            }
-        }
-
+        self.write_value(&value, args);
         self
     }
 
+    /// Write a value to the buffer with formatting arguments.
+    #[inline(always)]
+    fn write_value<T: Log>(&mut self, value: &T, args: &[Argument]) {
+        self.len = write_value(&mut self.buffer, self.len, value, args);
+    }
+
     /// Log the message in the buffer.
     #[inline(always)]
     pub fn log(&self) {
@@ -138,6 +132,298 @@ impl<const BUFFER: usize> Logger<BUFFER> {
     }
 }
 
+/// Logger that logs the buffer when it is full and continues on a new log line.
+///
+/// Values are not split across lines: when a value does not fit in the remaining
+/// space of the buffer, the current line is logged and the value is written at the
+/// start of a new line. Values larger than the buffer are truncated.
+///
+/// # Example
+///
+/// ```
+/// use pinocchio_log::logger::MultilineLogger;
+///
+/// let mut logger = MultilineLogger::<20>::default();
+///
+/// for balance in [1_000_000_000u64, 2_000_000_000, 3_000_000_000] {
+///     // Logs "balance=1000000000 " when appending the second balance.
+///     logger.append("balance=").append(balance).append(" ");
+/// }
+///
+/// // Logs the last line.
+/// logger.log();
+/// ```
+pub struct MultilineLogger<const BUFFER: usize> {
+    // Logger with the current line.
+    logger: Logger<BUFFER>,
+}
+
+impl<const BUFFER: usize> Default for MultilineLogger<BUFFER> {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            logger: Logger::default(),
+        }
+    }
+}
+
+impl<const BUFFER: usize> Deref for MultilineLogger<BUFFER> {
+    type Target = [u8];
+
+    fn deref(&self) -> &Self::Target {
+        &self.logger
+    }
+}
+
+impl<const BUFFER: usize> MultilineLogger<BUFFER> {
+    /// Append a value to the logger.
+    #[inline(always)]
+    pub fn append<T: Log>(&mut self, value: T) -> &mut Self {
+        self.append_with_args(value, &[])
+    }
+
+    /// Append a value to the logger with formatting arguments.
+    ///
+    /// The current line is logged first when the value does not fit in the
+    /// remaining space of the buffer.
+    #[inline]
+    pub fn append_with_args<T: Log>(&mut self, value: T, args: &[Argument]) -> &mut Self {
+        if self.logger.is_full() {
+            self.log();
+        }
+
+        let start = self.logger.len;
+        // Values with a maximum length that fits in the remaining space cannot be
+        // truncated.
+        let fits = matches!(
+            bounded_len(T::MAX_LEN, args),
+            Some(length) if length <= self.logger.remaining()
+        );
+
+        self.logger.write_value(&value, args);
+
+        // A truncated value is written again on a new line, unless it was already at
+        // the start of the line. Truncated values fill the buffer and end with the
+        // `TRUNCATED` character.
+        if !fits && start > 0 && self.logger.is_full() && self.last() == Some(&TRUNCATED) {
+            self.logger.len = start;
+            self.log();
+            self.logger.write_value(&value, args);
+        }
+
+        self
+    }
+
+    /// Log the current line, if not empty, and clear the buffer.
+    #[inline]
+    pub fn log(&mut self) {
+        if !self.logger.is_empty() {
+            self.logger.log();
+            self.logger.clear();
+        }
+    }
+}
+
+/// Formatter that writes values to a caller provided buffer.
+///
+/// The formatter works the same way as a [`Logger`], but the message is written to a
+/// borrowed buffer instead of being logged. This is useful to build byte strings (e.g.,
+/// memos, return data or seeds) without allocations. When the buffer is full, the last
+/// byte is set to `@` to indicate that the content was truncated.
+///
+/// # Example
+///
+/// ```
+/// use core::mem::MaybeUninit;
+/// use pinocchio_log::logger::Formatter;
+///
+/// let mut buffer = [MaybeUninit::<u8>::uninit(); 32];
+/// let mut formatter = Formatter::new(&mut buffer);
+/// formatter.append("vault-").append(42u64);
+///
+/// assert_eq!(formatter.finish(), b"vault-42");
+/// ```
+pub struct Formatter<'a> {
+    // Byte buffer to store the message.
+    buffer: &'a mut [MaybeUninit<u8>],
+
+    // Length of the message.
+    len: usize,
+}
+
+impl<'a> Formatter<'a> {
+    /// Creates a new `Formatter` that writes to the provided buffer.
+    ///
+    /// The buffer does not need to be initialized, since only the written part of it
+    /// is returned by [`Formatter::finish`].
+    #[inline(always)]
+    pub fn new(buffer: &'a mut [MaybeUninit<u8>]) -> Self {
+        Self { buffer, len: 0 }
+    }
+
+    /// Append a value to the formatter.
+    #[inline(always)]
+    pub fn append<T: Log>(&mut self, value: T) -> &mut Self {
+        self.append_with_args(value, &[])
+    }
+
+    /// Append a value to the formatter with formatting arguments.
+    #[inline]
+    pub fn append_with_args<T: Log>(&mut self, value: T, args: &[Argument]) -> &mut Self {
+        self.len = write_value(self.buffer, self.len, &value, args);
+        self
+    }
+
+    /// Clear the content of the formatter.
+    #[inline(always)]
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+
+    /// Check whether the buffer is at the maximum length or not.
+    #[inline(always)]
+    pub fn is_full(&self) -> bool {
+        self.len == self.buffer.len()
+    }
+
+    /// Get the remaining space in the buffer.
+    #[inline(always)]
+    pub fn remaining(&self) -> usize {
+        self.buffer.len() - self.len
+    }
+
+    /// Consumes the formatter, returning the written part of the buffer.
+    #[inline(always)]
+    pub fn finish(self) -> &'a [u8] {
+        // SAFETY: the slice is created from the buffer up to the length
+        // of the message.
+        unsafe { from_raw_parts(self.buffer.as_ptr() as *const _, self.len) }
+    }
+}
+
+impl Deref for Formatter<'_> {
+    type Target = [u8];
+
+    fn deref(&self) -> &Self::Target {
+        // SAFETY: the slice is created from the buffer up to the length
+        // of the message.
+        unsafe { from_raw_parts(self.buffer.as_ptr() as *const _, self.len) }
+    }
+}
+
+/// Write a value to the buffer at `len` with formatting arguments.
+///
+/// Returns the new length of the buffer content. When the value does not fit,
+/// the last byte of the buffer is set to `TRUNCATED`.
+#[inline]
+fn write_value<T: Log>(
+    buffer: &mut [MaybeUninit<u8>],
+    len: usize,
+    value: &T,
+    args: &[Argument],
+) -> usize {
+    if len == buffer.len() {
+        if let Some(last) = buffer.last_mut() {
+            last.write(TRUNCATED);
+        }
+        len
+    } else {
+        let start = len;
+        let mut len = len + value.write_with_args(&mut buffer[start..], args);
+
+        if let Some(Argument::Width(width)) =
+            args.iter().find(|arg| matches!(arg, Argument::Width(_)))
+        {
+            len = pad::<T>(buffer, start, len, *width, args);
+        }
+
+        if len > buffer.len() {
+            // Indicates that the buffer is full.
+            len = buffer.len();
+            // SAFETY: the buffer length is checked to be less than `len`.
+            unsafe {
+                let last = buffer.get_unchecked_mut(len - 1);
+                last.write(TRUNCATED);
+            }
+        }
+
+        len
+    }
+}
+
+/// Pad the value written at `start` to the specified width.
+///
+/// The width is measured in characters. Values are aligned according to the
+/// [`Argument::Align`] argument, defaulting to the [`Log::ALIGNMENT`] of the type;
+/// the [`Argument::ZeroPad`] argument pads with zeros after any sign or `0x` prefix
+/// instead. Returns the new length of the buffer content.
+#[inline]
+fn pad<T: Log>(
+    buffer: &mut [MaybeUninit<u8>],
+    start: usize,
+    len: usize,
+    width: usize,
+    args: &[Argument],
+) -> usize {
+    // SAFETY: the bytes up to `len` are initialized.
+    let value = unsafe { from_raw_parts(buffer.as_ptr().add(start) as *const u8, len - start) };
+    // UTF-8 continuation bytes do not start a new character.
+    let characters = value.iter().filter(|byte| (**byte & 0xc0) != 0x80).count();
+
+    if characters >= width {
+        return len;
+    }
+
+    let padding = width - characters;
+
+    let (fill, insert_at) = if args.iter().any(|arg| matches!(arg, Argument::ZeroPad)) {
+        let mut prefix = match value.first() {
+            Some(b'+' | b'-') => 1,
+            _ => 0,
+        };
+        if value[prefix..].starts_with(b"0x") {
+            prefix += 2;
+        }
+        (b'0', start + prefix)
+    } else {
+        let alignment = match args.iter().find(|arg| matches!(arg, Argument::Align(_))) {
+            Some(Argument::Align(alignment)) => *alignment,
+            _ => T::ALIGNMENT,
+        };
+        match alignment {
+            Alignment::Left => (b' ', len),
+            Alignment::Right => (b' ', start),
+        }
+    };
+
+    // The padding is clamped to the remaining space of the buffer, and the bytes
+    // after the insert position are moved to make space for it, discarding the
+    // ones that do not fit in the buffer.
+    let filled = core::cmp::min(padding, buffer.len() - insert_at);
+    let moved = core::cmp::min(len - insert_at, buffer.len() - insert_at - filled);
+
+    // SAFETY: `insert_at + filled` is at most the buffer length, and the bytes are
+    // only moved when there is space for them after the padding.
+    unsafe {
+        let ptr = buffer.as_mut_ptr().add(insert_at);
+        if moved > 0 {
+            core::ptr::copy(ptr, ptr.add(filled), moved);
+        }
+        core::ptr::write_bytes(ptr, fill, filled);
+    }
+
+    if len + padding > buffer.len() {
+        // SAFETY: the buffer is non-empty since there is a value to pad.
+        unsafe {
+            let last = buffer.get_unchecked_mut(buffer.len() - 1);
+            last.write(TRUNCATED);
+        }
+        buffer.len()
+    } else {
+        len + padding
+    }
+}
+
 /// Log a message.
 #[inline(always)]
 pub fn log_message(message: &[u8]) {
@@ -188,6 +474,97 @@ pub enum Argument {
     ///
     /// This is only applicable for `str` types.
     TruncateStart(usize),
+
+    /// Minimum number of characters to write, padding the output with spaces.
+    ///
+    /// The padding is added according to the [`Argument::Align`] argument or the
+    /// default alignment of the type.
+    Width(usize),
+
+    /// Alignment of the output when padded to the [`Argument::Width`].
+    Align(Alignment),
+
+    /// Pad the output with zeros after the sign, instead of spaces, when padded to
+    /// the [`Argument::Width`].
+    ///
+    /// This is only applicable for numeric types.
+    ZeroPad,
+
+    /// Write numbers in lowercase hexadecimal.
+    ///
+    /// This is only applicable for integer types. Negative numbers are written in
+    /// two's complement representation.
+    LowerHex,
+
+    /// Write numbers in uppercase hexadecimal.
+    ///
+    /// This is only applicable for integer types. Negative numbers are written in
+    /// two's complement representation.
+    UpperHex,
+
+    /// Prefix hexadecimal numbers with `0x`.
+    ///
+    /// This is only applicable together with [`Argument::LowerHex`] or [`Argument::UpperHex`].
+    Alternate,
+
+    /// Always write the sign of numbers, including `+` for positive numbers.
+    ///
+    /// This is only applicable for numeric types.
+    SignPlus,
+
+    /// Write numbers in exponent notation (e.g., `1.5e9`).
+    ///
+    /// The [`Argument::Precision`] specifies the number of digits after the decimal
+    /// point; remaining digits are truncated instead of rounded. When no precision is
+    /// specified, trailing zeros are omitted.
+    ///
+    /// This is only applicable for integer types.
+    LowerExp,
+}
+
+/// Alignment of a value padded to a minimum width.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Alignment {
+    /// Padding is added at the end of the value.
+    Left,
+
+    /// Padding is added at the start of the value.
+    Right,
+}
+
+/// Fixed-point number with a number of decimal places only known at runtime.
+///
+/// This is useful to log token amounts, where the number of decimals is specified
+/// by the mint. The value is written the same way as using the [`Argument::Precision`]
+/// argument.
+///
+/// # Example
+///
+/// ```
+/// use pinocchio_log::logger::{Decimal, Logger};
+///
+/// let decimals = 6;
+///
+/// let mut logger = Logger::<100>::default();
+/// logger.append("amount=").append(Decimal::new(1_500_000u64, decimals));
+///
+/// assert!(&*logger == "amount=1.500000".as_bytes());
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct Decimal<T> {
+    /// Value as an integer number of the smallest unit.
+    pub value: T,
+
+    /// Number of decimal places.
+    pub decimals: u8,
+}
+
+impl<T> Decimal<T> {
+    /// Creates a new `Decimal` from the integer value and number of decimal places.
+    #[inline(always)]
+    pub const fn new(value: T, decimals: u8) -> Self {
+        Self { value, decimals }
+    }
 }
 
 /// Trait to specify the log behavior for a type.
@@ -199,6 +576,16 @@ pub enum Argument {
 /// than the number of bytes written to the buffer will result in undefined behavior, since
 /// it will lead to reading uninitialized memory from the buffer.
 pub unsafe trait Log {
+    /// Alignment of the value when padded to a minimum width and no alignment
+    /// is specified.
+    const ALIGNMENT: Alignment = Alignment::Left;
+
+    /// Maximum number of bytes written for a value, or `None` when the length of
+    /// values is not bounded (e.g., `&str`).
+    ///
+    /// This is used by the `log!` macro to compute the buffer length at compile time.
+    const MAX_LEN: Option<usize> = None;
+
     #[inline(always)]
     fn debug(&self, buffer: &mut [MaybeUninit<u8>]) -> usize {
         self.debug_with_args(buffer, &[])
@@ -215,21 +602,348 @@ pub unsafe trait Log {
     }
 
     fn write_with_args(&self, buffer: &mut [MaybeUninit<u8>], parameters: &[Argument]) -> usize;
+
+    /// Write an array of values to the buffer.
+    ///
+    /// This is used by the implementation of the log trait for `&[T; N]`, allowing
+    /// element types to customize how arrays of them are logged.
+    #[doc(hidden)]
+    #[inline(always)]
+    fn write_array_with_args<const N: usize>(
+        values: &[Self; N],
+        buffer: &mut [MaybeUninit<u8>],
+        args: &[Argument],
+    ) -> usize
+    where
+        Self: Sized,
+    {
+        (values as &[Self]).write_with_args(buffer, args)
+    }
+}
+
+/// Trait for numeric types.
+///
+/// Numeric types support the [`Argument::ZeroPad`], [`Argument::LowerHex`],
+/// [`Argument::UpperHex`], [`Argument::Alternate`], [`Argument::SignPlus`] and
+/// [`Argument::LowerExp`] formatting arguments. The `log!` macro uses this trait
+/// to reject numeric format specifiers for other types at compile time.
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` does not support numeric format specifiers",
+    label = "expected an integer value"
+)]
+pub trait Numeric: Log {}
+
+/// Checks at compile time that the value is numeric.
+#[doc(hidden)]
+#[inline(always)]
+pub fn numeric<T: Numeric>(_value: &T) {}
+
+/// Trait for types that can be written in hexadecimal.
+///
+/// These are the numeric types and byte arrays (`[u8; N]` and `&[u8; N]`), which
+/// support the [`Argument::LowerHex`] and [`Argument::UpperHex`] formatting
+/// arguments. The `log!` macro uses this trait to reject the `{:x}` and `{:X}`
+/// format specifiers for other types at compile time.
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` does not support hexadecimal format specifiers",
+    label = "expected an integer value or a byte array"
+)]
+pub trait Hex: Log {}
+
+impl<T: Numeric> Hex for T {}
+
+impl<const N: usize> Hex for [u8; N] {}
+
+impl<const N: usize> Hex for &[u8; N] {}
+
+/// Checks at compile time that the value can be written in hexadecimal.
+#[doc(hidden)]
+#[inline(always)]
+pub fn hex<T: Hex>(_value: &T) {}
+
+/// Largest buffer length that the `log!` macro computes at compile time.
+///
+/// Messages that might be longer than this length result in a compile error when the
+/// buffer length is computed, i.e., with `log!(_, ...)`.
+pub const MAX_COMPUTED_BUFFER_LEN: usize = 1024;
+
+/// Returns the maximum number of bytes written for a value with formatting arguments.
+///
+/// The `max_len` is the [`Log::MAX_LEN`] of the value type. Arguments that can increase
+/// the length of the output are taken into account, while truncate arguments bound the
+/// length of values without a maximum length.
+///
+/// # Panics
+///
+/// Panics when the length of the value is not bounded, which results in a compile error
+/// when evaluated at compile time.
+#[doc(hidden)]
+pub const fn max_len(max_len: Option<usize>, args: &[Argument]) -> usize {
+    match bounded_len(max_len, args) {
+        Some(length) => length,
+        None => panic!(
+            "the length of the value is not bounded, use a truncate format (e.g., `{{:>.10}}`) \
+             or specify the buffer length"
+        ),
+    }
+}
+
+/// Returns the maximum number of bytes written for a value with formatting arguments,
+/// or `None` when the length of the value is not bounded.
+///
+/// See [`max_len`] for details on how the length is computed.
+const fn bounded_len(max_len: Option<usize>, args: &[Argument]) -> Option<usize> {
+    let mut length = max_len;
+    let mut extra = 0;
+    let mut width = 0;
+    let mut i = 0;
+
+    while i < args.len() {
+        match args[i] {
+            // Leading zeros, decimal point and the fractional digits.
+            Argument::Precision(precision) => extra += precision as usize + 2,
+            Argument::TruncateEnd(size) | Argument::TruncateStart(size) => {
+                length = match length {
+                    Some(length) if length < size => Some(length),
+                    _ => Some(size),
+                };
+            }
+            Argument::Width(size) => width = size,
+            // `0x` prefix.
+            Argument::Alternate => extra += 2,
+            Argument::SignPlus => extra += 1,
+            // Decimal point and exponent (at most `e38`).
+            Argument::LowerExp => extra += 4,
+            _ => (),
+        }
+        i += 1;
+    }
+
+    match length {
+        Some(length) if length + extra > width => Some(length + extra),
+        Some(_) => Some(width),
+        None => None,
+    }
+}
+
+/// Trait to write the digits of unsigned integers.
+///
+/// This allows signed integer types to write their absolute value without
+/// duplicating the handling of the sign.
+trait WriteUnsigned {
+    /// Write the value to the buffer, ignoring the [`Argument::SignPlus`] argument.
+    fn write_unsigned(&self, buffer: &mut [MaybeUninit<u8>], args: &[Argument]) -> usize;
+}
+
+/// Sequential writer over a buffer that marks truncated output.
+///
+/// This is used by the `Log` derive macro to write composite values.
+#[doc(hidden)]
+pub struct BufferWriter<'a> {
+    /// Buffer to write to.
+    buffer: &'a mut [MaybeUninit<u8>],
+
+    /// Number of bytes written.
+    written: usize,
+
+    /// Indicates whether there was not enough space in the buffer.
+    truncated: bool,
+}
+
+impl<'a> BufferWriter<'a> {
+    #[inline(always)]
+    pub fn new(buffer: &'a mut [MaybeUninit<u8>]) -> Self {
+        Self {
+            buffer,
+            written: 0,
+            truncated: false,
+        }
+    }
+
+    /// Write the bytes to the buffer, ignoring the ones that do not fit.
+    #[inline]
+    pub fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            if let Some(destination) = self.buffer.get_mut(self.written) {
+                destination.write(*byte);
+                self.written += 1;
+            } else {
+                self.truncated = true;
+                return;
+            }
+        }
+    }
+
+    /// Write to the remaining space of the buffer using the provided function.
+    ///
+    /// # Safety
+    ///
+    /// The function must return the number of bytes written to the buffer it
+    /// receives, as required by the [`Log`] trait.
+    #[inline]
+    pub unsafe fn write_with<F>(&mut self, f: F)
+    where
+        F: FnOnce(&mut [MaybeUninit<u8>]) -> usize,
+    {
+        if self.written == self.buffer.len() {
+            self.truncated = true;
+        } else {
+            self.written += f(&mut self.buffer[self.written..]);
+        }
+    }
+
+    /// Returns the number of bytes written, setting the last byte to `TRUNCATED`
+    /// when the output did not fit in the buffer.
+    #[inline]
+    pub fn finish(self) -> usize {
+        if self.truncated && self.written > 0 {
+            self.buffer[self.written - 1].write(TRUNCATED);
+        }
+        self.written
+    }
+}
+
+/// Write the decimal digits of a number in exponent notation.
+///
+/// The `digits` are the ASCII decimal digits of the number, without leading zeros.
+#[inline]
+fn write_exponent(
+    digits: &[u8],
+    precision: Option<usize>,
+    buffer: &mut [MaybeUninit<u8>],
+) -> usize {
+    let mut writer = BufferWriter::new(buffer);
+    let (first, fraction) = digits.split_at(1);
+
+    // Without a precision, trailing zeros of the fractional part are omitted.
+    let significant = fraction
+        .iter()
+        .rposition(|digit| *digit != b'0')
+        .map_or(0, |position| position + 1);
+    let precision = precision.unwrap_or(significant);
+
+    writer.write(first);
+
+    if precision > 0 {
+        writer.write(b".");
+
+        let length = core::cmp::min(precision, fraction.len());
+        writer.write(&fraction[..length]);
+
+        for _ in length..precision {
+            writer.write(b"0");
+        }
+    }
+
+    // The exponent is at most 38, for `u128::MAX`.
+    let exponent = fraction.len() as u8;
+    writer.write(b"e");
+
+    if exponent >= 10 {
+        writer.write(&[b'0' + exponent / 10]);
+    }
+    writer.write(&[b'0' + exponent % 10]);
+
+    writer.finish()
 }
 
 /// Implement the log trait for unsigned integer types.
 macro_rules! impl_log_for_unsigned_integer {
-    ( $type:tt ) => {
+    ( $type:tt $( { $($items:item)* } )? ) => {
         unsafe impl Log for $type {
+            $( $($items)* )?
+
+            const ALIGNMENT: Alignment = Alignment::Right;
+
+            const MAX_LEN: Option<usize> = Some($type::MAX.ilog10() as usize + 1);
+
             #[inline]
             fn write_with_args(&self, buffer: &mut [MaybeUninit<u8>], args: &[Argument]) -> usize {
+                if buffer.is_empty() {
+                    return 0;
+                }
+
+                if args.iter().any(|arg| matches!(arg, Argument::SignPlus)) {
+                    // SAFETY: the buffer is checked to be non-empty.
+                    unsafe {
+                        buffer.get_unchecked_mut(0).write(b'+');
+                    }
+                    1 + self.write_unsigned(&mut buffer[1..], args)
+                } else {
+                    self.write_unsigned(buffer, args)
+                }
+            }
+        }
+
+        impl Numeric for $type {}
+
+        impl WriteUnsigned for $type {
+            #[inline]
+            fn write_unsigned(&self, buffer: &mut [MaybeUninit<u8>], args: &[Argument]) -> usize {
                 // The maximum number of digits that the type can have.
                 const MAX_DIGITS: usize = $type::MAX.ilog10() as usize + 1;
 
+                // The maximum number of hexadecimal digits that the type can have.
+                const MAX_HEX_DIGITS: usize = core::mem::size_of::<$type>() * 2;
+
                 if buffer.is_empty() {
                     return 0;
                 }
 
+                let hex_digits = args.iter().find_map(|arg| match arg {
+                    Argument::LowerHex => Some(HEX_DIGITS),
+                    Argument::UpperHex => Some(UPPER_HEX_DIGITS),
+                    _ => None,
+                });
+
+                if let Some(hex_digits) = hex_digits {
+                    let mut digits = [0u8; MAX_HEX_DIGITS];
+                    let mut offset = MAX_HEX_DIGITS;
+                    let mut value = *self;
+
+                    loop {
+                        offset -= 1;
+                        digits[offset] = hex_digits[(value & 0xf) as usize];
+                        value >>= 4;
+
+                        if value == 0 {
+                            break;
+                        }
+                    }
+
+                    let mut writer = BufferWriter::new(buffer);
+
+                    if args.iter().any(|arg| matches!(arg, Argument::Alternate)) {
+                        writer.write(b"0x");
+                    }
+                    writer.write(&digits[offset..]);
+
+                    return writer.finish();
+                }
+
+                if args.iter().any(|arg| matches!(arg, Argument::LowerExp)) {
+                    let mut digits = [0u8; MAX_DIGITS];
+                    let mut offset = MAX_DIGITS;
+                    let mut value = *self;
+
+                    loop {
+                        offset -= 1;
+                        digits[offset] = b'0' + (value % 10) as u8;
+                        value /= 10;
+
+                        if value == 0 {
+                            break;
+                        }
+                    }
+
+                    let precision = args.iter().find_map(|arg| match arg {
+                        Argument::Precision(precision) => Some(*precision as usize),
+                        _ => None,
+                    });
+
+                    return write_exponent(&digits[offset..], precision, buffer);
+                }
+
                 match *self {
                     // Handle zero as a special case.
                     0 => {
@@ -369,7 +1083,25 @@ macro_rules! impl_log_for_unsigned_integer {
 }
 
 // Supported unsigned integer types.
-impl_log_for_unsigned_integer!(u8);
+impl_log_for_unsigned_integer!(u8 {
+    /// Arrays of bytes are written in hexadecimal with the [`Argument::LowerHex`] and
+    /// [`Argument::UpperHex`] arguments; otherwise, they are written as a list of values.
+    #[inline]
+    fn write_array_with_args<const N: usize>(
+        values: &[u8; N],
+        buffer: &mut [MaybeUninit<u8>],
+        args: &[Argument],
+    ) -> usize {
+        if args
+            .iter()
+            .any(|arg| matches!(arg, Argument::LowerHex | Argument::UpperHex))
+        {
+            write_hex(values, buffer, args)
+        } else {
+            (values as &[u8]).write_with_args(buffer, args)
+        }
+    }
+});
 impl_log_for_unsigned_integer!(u16);
 impl_log_for_unsigned_integer!(u32);
 impl_log_for_unsigned_integer!(u64);
@@ -378,51 +1110,60 @@ impl_log_for_unsigned_integer!(usize);
 
 /// Implement the log trait for the signed integer types.
 macro_rules! impl_log_for_signed {
-    ( $type:tt ) => {
+    ( $type:tt, $unsigned:tt ) => {
         unsafe impl Log for $type {
+            const ALIGNMENT: Alignment = Alignment::Right;
+
+            // Digits of the absolute value and the sign.
+            const MAX_LEN: Option<usize> = Some($type::MIN.unsigned_abs().ilog10() as usize + 2);
+
             #[inline]
             fn write_with_args(&self, buffer: &mut [MaybeUninit<u8>], args: &[Argument]) -> usize {
                 if buffer.is_empty() {
                     return 0;
                 }
 
-                match *self {
-                    // Handle zero as a special case.
-                    0 => {
-                        // SAFETY: the buffer is checked to be non-empty.
-                        unsafe {
-                            buffer.get_unchecked_mut(0).write(b'0');
-                        }
-                        1
-                    }
-                    value => {
-                        let mut prefix = 0;
+                // Hexadecimal values are written in two's complement representation.
+                if args
+                    .iter()
+                    .any(|arg| matches!(arg, Argument::LowerHex | Argument::UpperHex))
+                {
+                    return (*self as $unsigned).write_unsigned(buffer, args);
+                }
 
-                        if *self < 0 {
-                            // SAFETY: the buffer is checked to be non-empty.
-                            unsafe {
-                                buffer.get_unchecked_mut(0).write(b'-');
-                            }
-                            prefix += 1;
-                        };
+                let sign = if *self < 0 {
+                    Some(b'-')
+                } else if args.iter().any(|arg| matches!(arg, Argument::SignPlus)) {
+                    Some(b'+')
+                } else {
+                    None
+                };
 
-                        prefix
-                            + $type::unsigned_abs(value)
-                                .write_with_args(&mut buffer[prefix..], args)
+                let mut prefix = 0;
+
+                if let Some(sign) = sign {
+                    // SAFETY: the buffer is checked to be non-empty.
+                    unsafe {
+                        buffer.get_unchecked_mut(0).write(sign);
                     }
+                    prefix += 1;
                 }
+
+                prefix + $type::unsigned_abs(*self).write_unsigned(&mut buffer[prefix..], args)
             }
         }
+
+        impl Numeric for $type {}
     };
 }
 
 // Supported signed integer types.
-impl_log_for_signed!(i8);
-impl_log_for_signed!(i16);
-impl_log_for_signed!(i32);
-impl_log_for_signed!(i64);
-impl_log_for_signed!(i128);
-impl_log_for_signed!(isize);
+impl_log_for_signed!(i8, u8);
+impl_log_for_signed!(i16, u16);
+impl_log_for_signed!(i32, u32);
+impl_log_for_signed!(i64, u64);
+impl_log_for_signed!(i128, u128);
+impl_log_for_signed!(isize, usize);
 
 /// Implement the log trait for the `&str` type.
 unsafe impl Log for &str {
@@ -595,7 +1336,16 @@ macro_rules! impl_log_for_slice {
         where
             $type: Log
         {
-            impl_log_for_slice!(@generate_write);
+            // Brackets and the values separated by `, `.
+            const MAX_LEN: Option<usize> = match $type::MAX_LEN {
+                Some(length) => Some(2 + $size * (length + 2)),
+                None => None,
+            };
+
+            #[inline(always)]
+            fn write_with_args(&self, buffer: &mut [MaybeUninit<u8>], args: &[Argument]) -> usize {
+                $type::write_array_with_args(*self, buffer, args)
+            }
         }
     };
     ( @generate_write ) => {
@@ -668,6 +1418,8 @@ impl_log_for_slice!([T; N]);
 
 /// Implement the log trait for the `bool` type.
 unsafe impl Log for bool {
+    const MAX_LEN: Option<usize> = Some(5);
+
     #[inline]
     fn debug_with_args(&self, buffer: &mut [MaybeUninit<u8>], args: &[Argument]) -> usize {
         let value = if *self { "true" } else { "false" };
@@ -680,3 +1432,180 @@ unsafe impl Log for bool {
         value.write_with_args(buffer, args)
     }
 }
+
+/// Implement the log trait for byte arrays.
+///
+/// Byte arrays are written the same way as references to them (`&[u8; N]`): as a
+/// list of values, or in hexadecimal with the [`Argument::LowerHex`] and
+/// [`Argument::UpperHex`] arguments.
+unsafe impl<const N: usize> Log for [u8; N] {
+    const MAX_LEN: Option<usize> = <&[u8; N]>::MAX_LEN;
+
+    #[inline(always)]
+    fn write_with_args(&self, buffer: &mut [MaybeUninit<u8>], args: &[Argument]) -> usize {
+        u8::write_array_with_args(self, buffer, args)
+    }
+}
+
+/// Implement the log trait for the `Option` type.
+///
+/// The value is written when present; otherwise, `None` is written.
+unsafe impl<T: Log> Log for Option<T> {
+    const ALIGNMENT: Alignment = T::ALIGNMENT;
+
+    // The value or `None`.
+    const MAX_LEN: Option<usize> = match T::MAX_LEN {
+        Some(length) if length < 4 => Some(4),
+        length => length,
+    };
+
+    #[inline]
+    fn debug_with_args(&self, buffer: &mut [MaybeUninit<u8>], args: &[Argument]) -> usize {
+        match self {
+            Some(value) => value.debug_with_args(buffer, args),
+            None => "None".write_with_args(buffer, &[]),
+        }
+    }
+
+    #[inline]
+    fn write_with_args(&self, buffer: &mut [MaybeUninit<u8>], args: &[Argument]) -> usize {
+        match self {
+            Some(value) => value.write_with_args(buffer, args),
+            None => "None".write_with_args(buffer, &[]),
+        }
+    }
+}
+
+/// Implement the log trait for the `char` type.
+unsafe impl Log for char {
+    const MAX_LEN: Option<usize> = Some(4);
+
+    #[inline]
+    fn write_with_args(&self, buffer: &mut [MaybeUninit<u8>], args: &[Argument]) -> usize {
+        let mut encoded = [0u8; 4];
+        (&*self.encode_utf8(&mut encoded)).write_with_args(buffer, args)
+    }
+}
+
+/// Implement the log trait for fixed-point numbers.
+unsafe impl<T: Log> Log for Decimal<T> {
+    const ALIGNMENT: Alignment = T::ALIGNMENT;
+
+    // The value can have up to `u8::MAX` decimal places, together with the sign,
+    // leading zero and decimal point.
+    const MAX_LEN: Option<usize> = match T::MAX_LEN {
+        Some(length) if length > u8::MAX as usize + 1 => Some(length + 2),
+        Some(_) => Some(u8::MAX as usize + 3),
+        None => None,
+    };
+
+    /// The [`Argument::SignPlus`] argument is forwarded to the value, while the
+    /// precision is always the number of decimal places.
+    #[inline]
+    fn write_with_args(&self, buffer: &mut [MaybeUninit<u8>], args: &[Argument]) -> usize {
+        let precision = Argument::Precision(self.decimals);
+
+        if args.iter().any(|arg| matches!(arg, Argument::SignPlus)) {
+            self.value
+                .write_with_args(buffer, &[precision, Argument::SignPlus])
+        } else {
+            self.value.write_with_args(buffer, &[precision])
+        }
+    }
+}
+
+/// Writes the bytes to the buffer as hexadecimal digits.
+///
+/// The digits are lowercase, or uppercase with the [`Argument::UpperHex`] argument,
+/// and can be truncated with the [`Argument::TruncateEnd`] and
+/// [`Argument::TruncateStart`] arguments.
+#[inline]
+fn write_hex(bytes: &[u8], buffer: &mut [MaybeUninit<u8>], args: &[Argument]) -> usize {
+    let hex_digits = if args.iter().any(|arg| matches!(arg, Argument::UpperHex)) {
+        UPPER_HEX_DIGITS
+    } else {
+        HEX_DIGITS
+    };
+    // Writes the hex digits in the range, where each byte has two digits.
+    let write_digits = |writer: &mut BufferWriter, digits: core::ops::Range<usize>| {
+        for digit in digits {
+            let byte = bytes[digit / 2];
+            let nibble = if digit % 2 == 0 {
+                byte >> 4
+            } else {
+                byte & 0xf
+            };
+            writer.write(&[hex_digits[nibble as usize]]);
+        }
+    };
+
+    let length = bytes.len() * 2;
+    let truncate = args.iter().find_map(|arg| match arg {
+        Argument::TruncateEnd(size) => Some((*size, true)),
+        Argument::TruncateStart(size) => Some((*size, false)),
+        _ => None,
+    });
+
+    match truncate {
+        // The digits are truncated to the maximum length, followed or preceded by the
+        // `TRUNCATED_SLICE` when there is space for it; otherwise, the last character
+        // is set to `TRUNCATED`.
+        Some((size, truncate_end)) if size.min(buffer.len()) < length => {
+            let max_length = size.min(buffer.len());
+            let mut writer = BufferWriter::new(&mut buffer[..max_length]);
+
+            if max_length > TRUNCATED_SLICE.len() {
+                let visible = max_length - TRUNCATED_SLICE.len();
+
+                if truncate_end {
+                    write_digits(&mut writer, 0..visible);
+                    writer.write(&TRUNCATED_SLICE);
+                } else {
+                    writer.write(&TRUNCATED_SLICE);
+                    write_digits(&mut writer, length - visible..length);
+                }
+            } else {
+                write_digits(&mut writer, 0..length);
+            }
+
+            writer.finish()
+        }
+        _ => {
+            let mut writer = BufferWriter::new(buffer);
+            write_digits(&mut writer, 0..length);
+            writer.finish()
+        }
+    }
+}
+
+/// Writes the base58 representation of an address to the buffer.
+///
+/// The encoded address is written as a `&str`, so the truncate arguments apply to it.
+#[cfg(feature = "pubkey")]
+#[inline]
+fn write_base58(
+    address: &pinocchio_pubkey::reexport::Pubkey,
+    buffer: &mut [MaybeUninit<u8>],
+    args: &[Argument],
+) -> usize {
+    let mut encoded = [0u8; MAX_BASE58_LEN];
+    let length = encode_base58(address, &mut encoded);
+    // SAFETY: the base58 alphabet only contains ASCII characters.
+    let encoded = unsafe { core::str::from_utf8_unchecked(&encoded[..length]) };
+    encoded.write_with_args(buffer, args)
+}
+
+/// Implement the log trait for base58 addresses.
+///
+/// The address is written as its base58 representation. This is the only way to log
+/// an address in base58, since byte arrays are always written as a list of values
+/// or in hexadecimal.
+#[cfg(feature = "pubkey")]
+unsafe impl Log for pinocchio_pubkey::base58::Address {
+    const MAX_LEN: Option<usize> = Some(MAX_BASE58_LEN);
+
+    #[inline]
+    fn write_with_args(&self, buffer: &mut [MaybeUninit<u8>], args: &[Argument]) -> usize {
+        write_base58(&self.0, buffer, args)
+    }
+}
@@ -2,72 +2,488 @@
 
 extern crate alloc;
 
-use alloc::{format, string::ToString, vec::Vec};
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
 use proc_macro::TokenStream;
-use quote::quote;
-use regex::Regex;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{quote, quote_spanned};
 use syn::{
     parse::{Parse, ParseStream},
-    parse_macro_input, parse_str,
-    punctuated::Punctuated,
-    Error, Expr, ItemFn, LitInt, LitStr, Token,
+    parse_macro_input, parse_quote, parse_str,
+    spanned::Spanned,
+    Data, DeriveInput, Error, Expr, Field, Fields, Ident, ItemFn, Lit, LitInt, LitStr, Meta,
+    NestedMeta, Token,
 };
 
 /// The default buffer size for the logger.
 const DEFAULT_BUFFER_SIZE: &str = "200";
 
+/// Buffer lengths used when the buffer length is computed at compile time.
+///
+/// The smallest length that fits the message is used, since the length of a
+/// `Logger` cannot depend on the types of the arguments. Longer messages use
+/// `pinocchio_log::logger::MAX_COMPUTED_BUFFER_LEN`.
+const COMPUTED_BUFFER_SIZES: [usize; 5] = [32, 64, 128, 256, 512];
+
 /// Represents the input arguments to the `log!` macro.
 struct LogArgs {
     /// The length of the buffer to use for the logger.
     ///
     /// This does not have effect when the literal `str` does
-    /// not have value placeholders.
-    buffer_len: LitInt,
+    /// not have value placeholders. The length is computed at
+    /// compile time when `_` is specified, represented as `None`.
+    buffer_len: Option<LitInt>,
+
+    /// Indicates whether the message is logged with a `MultilineLogger`, which
+    /// continues on a new log line when the buffer is full.
+    multiline: bool,
 
     /// The literal formatting string passed to the macro.
     ///
     /// The `str` might have value placeholders. While this is
-    /// not a requirement, the number of placeholders must
-    /// match the number of args.
+    /// not a requirement, every argument must be referenced by
+    /// at least one placeholder.
     format_string: LitStr,
 
-    /// The arguments passed to the macro.
+    /// The positional arguments passed to the macro.
     ///
     /// The arguments represent the values to replace the
     /// placeholders on the format `str`. Valid values must implement
     /// the [`Log`] trait.
-    args: Punctuated<Expr, Token![,]>,
+    args: Vec<Expr>,
+
+    /// The named arguments passed to the macro (e.g., `x = a + b`).
+    ///
+    /// Named arguments always follow the positional arguments.
+    named_args: Vec<(Ident, Expr)>,
 }
 
 impl Parse for LogArgs {
     fn parse(input: ParseStream) -> syn::Result<Self> {
+        // Optional multiline mode, followed by the buffer length.
+        let multiline = input.peek(Ident) && input.peek2(Token![=]) && {
+            let ident = input.parse::<Ident>()?;
+
+            if ident != "multiline" {
+                return Err(Error::new_spanned(
+                    ident,
+                    "expected `multiline = <buffer length>`",
+                ));
+            }
+            input.parse::<Token![=]>()?;
+
+            if !input.peek(LitInt) {
+                return Err(input.error("expected the buffer length of the multiline logger"));
+            }
+            true
+        };
+
         // Optional buffer length.
         let buffer_len = if input.peek(LitInt) {
             let literal = input.parse()?;
             // Parse the comma after the buffer length.
             input.parse::<Token![,]>()?;
-            literal
+            Some(literal)
+        } else if input.peek(Token![_]) {
+            input.parse::<Token![_]>()?;
+            // Parse the comma after the buffer length.
+            input.parse::<Token![,]>()?;
+            None
         } else {
-            parse_str::<LitInt>(DEFAULT_BUFFER_SIZE)?
+            Some(parse_str::<LitInt>(DEFAULT_BUFFER_SIZE)?)
         };
 
         let format_string = input.parse()?;
-        // Check if there are any arguments passed to the macro.
-        let args = if input.is_empty() {
-            Punctuated::new()
-        } else {
-            input.parse::<Token![,]>()?;
-            Punctuated::parse_terminated(input)?
-        };
+        let (args, named_args) = parse_arguments(input)?;
 
         Ok(LogArgs {
             buffer_len,
+            multiline,
             format_string,
             args,
+            named_args,
         })
     }
 }
 
+/// Represents the input arguments to the `format_into!` macro.
+struct FormatIntoArgs {
+    /// The buffer to write the formatted message to.
+    buffer: Expr,
+
+    /// The literal formatting string passed to the macro.
+    format_string: LitStr,
+
+    /// The positional arguments passed to the macro.
+    args: Vec<Expr>,
+
+    /// The named arguments passed to the macro.
+    named_args: Vec<(Ident, Expr)>,
+}
+
+impl Parse for FormatIntoArgs {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let buffer = input.parse()?;
+        // Parse the comma after the buffer.
+        input.parse::<Token![,]>()?;
+
+        let format_string = input.parse()?;
+        let (args, named_args) = parse_arguments(input)?;
+
+        Ok(FormatIntoArgs {
+            buffer,
+            format_string,
+            args,
+            named_args,
+        })
+    }
+}
+
+/// A named argument (e.g., `x = a + b`).
+type NamedArgument = (Ident, Expr);
+
+/// Parses the arguments that follow the format string.
+///
+/// Returns the positional and named arguments, in this order.
+fn parse_arguments(input: ParseStream) -> syn::Result<(Vec<Expr>, Vec<NamedArgument>)> {
+    let mut args = Vec::new();
+    let mut named_args: Vec<NamedArgument> = Vec::new();
+
+    while !input.is_empty() {
+        input.parse::<Token![,]>()?;
+        // Allow a trailing comma.
+        if input.is_empty() {
+            break;
+        }
+
+        if input.peek(Ident) && input.peek2(Token![=]) && !input.peek2(Token![==]) {
+            let name: Ident = input.parse()?;
+            input.parse::<Token![=]>()?;
+            let value = input.parse()?;
+
+            if named_args.iter().any(|(existing, _)| *existing == name) {
+                return Err(Error::new_spanned(
+                    &name,
+                    format!("duplicate argument named `{}`", name),
+                ));
+            }
+
+            named_args.push((name, value));
+        } else {
+            let value: Expr = input.parse()?;
+
+            if !named_args.is_empty() {
+                return Err(Error::new_spanned(
+                    value,
+                    "positional arguments cannot follow named arguments",
+                ));
+            }
+
+            args.push(value);
+        }
+    }
+
+    Ok((args, named_args))
+}
+
+/// Formatting options of a placeholder.
+///
+/// The options follow the [`format!`](https://doc.rust-lang.org/std/fmt/) syntax
+/// `[[fill]align][sign]['#']['0'][width]['.' precision][type]`, with the exception
+/// that a fill character is not supported and `<` or `>` followed only by a precision
+/// truncate the start or end of a `str`.
+#[derive(Default)]
+struct FormatSpec {
+    /// Explicit alignment (`<` or `>`).
+    align: Option<char>,
+
+    /// Whether the `+` sign flag is set.
+    sign_plus: bool,
+
+    /// Whether the `#` alternate flag is set.
+    alternate: bool,
+
+    /// Whether the `0` flag is set.
+    zero_pad: bool,
+
+    /// Minimum width.
+    width: Option<usize>,
+
+    /// Number of decimal places.
+    precision: Option<u8>,
+
+    /// Truncation size, where the flag indicates whether the start is truncated.
+    truncate: Option<(bool, usize)>,
+
+    /// Formatting type (`x`, `X` or `e`).
+    format_type: Option<char>,
+}
+
+impl FormatSpec {
+    /// Parses the formatting options of a placeholder, e.g. `>10` for `{:>10}`.
+    fn parse(spec: &str) -> Result<Self, String> {
+        let mut result = FormatSpec::default();
+        let mut rest = spec;
+
+        if let Some(align @ ('<' | '>')) = rest.chars().next() {
+            rest = &rest[1..];
+
+            // Truncate format: `<.N` or `>.N`.
+            if let Some(size) = rest.strip_prefix('.') {
+                let size = size
+                    .parse::<usize>()
+                    .map_err(|_| format!("invalid truncate size format: {{:{}}}", spec))?;
+                result.truncate = Some((align == '<', size));
+                return Ok(result);
+            }
+
+            result.align = Some(align);
+        }
+
+        if let Some(value) = rest.strip_prefix('+') {
+            result.sign_plus = true;
+            rest = value;
+        }
+
+        if let Some(value) = rest.strip_prefix('#') {
+            result.alternate = true;
+            rest = value;
+        }
+
+        if let Some(value) = rest.strip_prefix('0') {
+            result.zero_pad = true;
+            rest = value;
+        }
+
+        let digits = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(rest.len());
+
+        if digits > 0 {
+            result.width = Some(
+                rest[..digits]
+                    .parse()
+                    .map_err(|_| format!("invalid width format: {{:{}}}", spec))?,
+            );
+            rest = &rest[digits..];
+        }
+
+        if let Some(value) = rest.strip_prefix('.') {
+            let digits = value
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(value.len());
+            result.precision = Some(
+                value[..digits]
+                    .parse()
+                    .map_err(|_| format!("invalid precision format: {{:{}}}", spec))?,
+            );
+            rest = &value[digits..];
+        }
+
+        match rest {
+            "" => (),
+            "x" | "X" | "e" => result.format_type = rest.chars().next(),
+            _ => return Err(format!("unsupported format specifier: {{:{}}}", spec)),
+        }
+
+        let hex = matches!(result.format_type, Some('x' | 'X'));
+
+        if result.alternate && !hex {
+            return Err(format!(
+                "alternate flag `#` is only supported for hexadecimal values: {{:{}}}",
+                spec
+            ));
+        }
+
+        if hex && result.precision.is_some() {
+            return Err(format!(
+                "precision is not supported for hexadecimal values: {{:{}}}",
+                spec
+            ));
+        }
+
+        if hex && result.sign_plus {
+            return Err(format!(
+                "sign flag `+` is not supported for hexadecimal values: {{:{}}}",
+                spec
+            ));
+        }
+
+        if result.zero_pad && result.align.is_some() {
+            return Err(format!(
+                "zero padding cannot be combined with alignment: {{:{}}}",
+                spec
+            ));
+        }
+
+        if (result.zero_pad || result.align.is_some()) && result.width.is_none() {
+            return Err(format!("missing width: {{:{}}}", spec));
+        }
+
+        Ok(result)
+    }
+
+    /// Indicates whether the options are only supported by numeric types.
+    fn is_numeric(&self) -> bool {
+        self.sign_plus || self.zero_pad || self.format_type.is_some()
+    }
+
+    /// Indicates whether the options are only the hexadecimal format, which is also
+    /// supported by byte arrays.
+    fn is_hex(&self) -> bool {
+        matches!(self.format_type, Some('x' | 'X')) && !self.alternate && !self.zero_pad
+    }
+
+    /// Generates the `Argument` values for the options.
+    fn arguments(&self) -> Vec<TokenStream2> {
+        let mut arguments = Vec::new();
+
+        if let Some(precision) = self.precision {
+            arguments.push(quote! { pinocchio_log::logger::Argument::Precision(#precision) });
+        }
+        match self.truncate {
+            Some((true, size)) => {
+                arguments.push(quote! { pinocchio_log::logger::Argument::TruncateStart(#size) })
+            }
+            Some((false, size)) => {
+                arguments.push(quote! { pinocchio_log::logger::Argument::TruncateEnd(#size) })
+            }
+            None => (),
+        }
+        if let Some(width) = self.width {
+            arguments.push(quote! { pinocchio_log::logger::Argument::Width(#width) });
+        }
+        match self.align {
+            Some('<') => arguments.push(quote! {
+                pinocchio_log::logger::Argument::Align(pinocchio_log::logger::Alignment::Left)
+            }),
+            Some(_) => arguments.push(quote! {
+                pinocchio_log::logger::Argument::Align(pinocchio_log::logger::Alignment::Right)
+            }),
+            None => (),
+        }
+        if self.zero_pad {
+            arguments.push(quote! { pinocchio_log::logger::Argument::ZeroPad });
+        }
+        if self.sign_plus {
+            arguments.push(quote! { pinocchio_log::logger::Argument::SignPlus });
+        }
+        if self.alternate {
+            arguments.push(quote! { pinocchio_log::logger::Argument::Alternate });
+        }
+        match self.format_type {
+            Some('x') => arguments.push(quote! { pinocchio_log::logger::Argument::LowerHex }),
+            Some('X') => arguments.push(quote! { pinocchio_log::logger::Argument::UpperHex }),
+            Some(_) => arguments.push(quote! { pinocchio_log::logger::Argument::LowerExp }),
+            None => (),
+        }
+
+        arguments
+    }
+}
+
+/// Reference to the argument of a placeholder.
+enum ArgumentRef {
+    /// The next positional argument (`{}`).
+    Next,
+
+    /// Explicit positional argument (e.g., `{0}`).
+    Index(usize),
+
+    /// Named argument or captured identifier (e.g., `{amount}`).
+    Name(String),
+}
+
+/// Piece of a parsed format string.
+enum Piece {
+    /// Literal text, with `{{` and `}}` escapes already replaced.
+    Literal(String),
+
+    /// Placeholder to be replaced by an argument value.
+    Placeholder(ArgumentRef, FormatSpec),
+}
+
+/// Parses a format string into literal and placeholder pieces.
+fn parse_format_string(value: &str) -> Result<Vec<Piece>, String> {
+    let mut pieces = Vec::new();
+    let mut literal = String::new();
+    let mut rest = value;
+
+    while let Some(position) = rest.find(['{', '}']) {
+        literal.push_str(&rest[..position]);
+        let tail = &rest[position..];
+
+        if let Some(value) = tail.strip_prefix("{{") {
+            literal.push('{');
+            rest = value;
+        } else if let Some(value) = tail.strip_prefix("}}") {
+            literal.push('}');
+            rest = value;
+        } else if tail.starts_with('}') {
+            return Err("invalid format string: unmatched `}` found".to_string());
+        } else {
+            let end = tail.find('}').ok_or_else(|| {
+                "invalid format string: expected `}` but string was terminated".to_string()
+            })?;
+
+            if !literal.is_empty() {
+                pieces.push(Piece::Literal(core::mem::take(&mut literal)));
+            }
+
+            let (argument, spec) = match tail[1..end].split_once(':') {
+                Some((argument, spec)) => (argument, Some(spec)),
+                None => (&tail[1..end], None),
+            };
+
+            let argument = if argument.is_empty() {
+                ArgumentRef::Next
+            } else if argument.bytes().all(|c| c.is_ascii_digit()) {
+                ArgumentRef::Index(
+                    argument
+                        .parse()
+                        .map_err(|_| format!("invalid argument index `{}`", argument))?,
+                )
+            } else if is_identifier(argument) {
+                ArgumentRef::Name(argument.to_string())
+            } else {
+                return Err(format!("invalid argument name `{}`", argument));
+            };
+
+            let spec = match spec {
+                Some(spec) => FormatSpec::parse(spec)?,
+                None => FormatSpec::default(),
+            };
+
+            pieces.push(Piece::Placeholder(argument, spec));
+            rest = &tail[end + 1..];
+        }
+    }
+
+    literal.push_str(rest);
+
+    if !literal.is_empty() {
+        pieces.push(Piece::Literal(literal));
+    }
+
+    Ok(pieces)
+}
+
+/// Checks whether the value is a valid identifier for a named argument.
+fn is_identifier(value: &str) -> bool {
+    let mut chars = value.chars();
+
+    match chars.next() {
+        Some(first) if first.is_alphabetic() || first == '_' => {
+            value != "_" && chars.all(|c| c.is_alphanumeric() || c == '_')
+        }
+        _ => false,
+    }
+}
+
 /// Companion `log!` macro for `pinocchio-log`.
 ///
 /// The macro automates the creation of a `Logger` object to log a message.
@@ -75,31 +491,284 @@ impl Parse for LogArgs {
 /// The macro parses the format string at compile time and generates the calls to a `Logger`
 /// object to generate the corresponding formatted message.
 ///
+/// # Format specifiers
+///
+/// - `{:.N}`: number of decimal places for numeric values.
+/// - `{:<.N}` and `{:>.N}`: truncate the start or end of a `str` to `N` characters.
+/// - `{:N}`, `{:<N}` and `{:>N}`: pad the value with spaces to a minimum width of `N`
+///   characters. Numbers are right-aligned and other values left-aligned by default.
+/// - `{:0N}`: pad a numeric value with zeros after the sign to a minimum width of `N`.
+/// - `{:x}`, `{:X}`, `{:#x}` and `{:#X}`: write an integer in hexadecimal, optionally
+///   prefixed with `0x`.
+/// - `{:x}` and `{:X}`: write a byte array (`[u8; N]` or `&[u8; N]`) in hexadecimal;
+///   otherwise, byte arrays are written as a list of values.
+/// - `{:+}`: always write the sign of a numeric value.
+/// - `{:e}` and `{:.Ne}`: write an integer in exponent notation, truncated to `N` decimal
+///   places when a precision is specified.
+///
+/// Numeric specifiers used with arguments that are not numeric result in a compile error.
+///
 /// # Arguments
 ///
 /// - `buffer_len`: The length of the buffer to use for the logger (default to `200`). This is an optional argument.
+///   When `_` is specified, the length is computed at compile time from the length of the format string
+///   and the maximum length of the arguments (`Log::MAX_LEN`), rounded up to the next power of two (up to
+///   `MAX_COMPUTED_BUFFER_LEN`). Arguments without a maximum length, such as `&str`, must then use a
+///   truncate format (e.g., `{:>.10}`).
+///
+///   When `multiline = N` is specified, the message is logged with a `MultilineLogger` with a buffer
+///   length of `N`, which logs the current line and continues on a new one when a value does not fit
+///   in the buffer, instead of truncating the message.
 /// - `format_string`: The literal string to log. This string can contain placeholders `{}` to be replaced by the arguments.
 /// - `args`: The arguments to replace the placeholders in the format string. The arguments must implement the `Log` trait.
+///
+/// # Placeholders
+///
+/// As with [`format_args!`](https://doc.rust-lang.org/core/macro.format_args.html), placeholders
+/// can reference arguments implicitly by position (`{}`), explicitly by index (`{0}`), by name
+/// (`log!("{x}", x = a + b)`) or capture an identifier in scope (`log!("amount={amount}")`).
+/// Each argument is evaluated once, and arguments referenced by more than one placeholder must be
+/// `Copy`. Literal braces are written as `{{` and `}}`.
 #[proc_macro]
 pub fn log(input: TokenStream) -> TokenStream {
     // Parse the input into a `LogArgs`.
     let LogArgs {
         buffer_len,
+        multiline,
         format_string,
         args,
+        named_args,
     } = parse_macro_input!(input as LogArgs);
-    let parsed_string = format_string.value();
 
-    // Regex pattern to match placeholders in the format string.
-    let placeholder_regex = Regex::new(r"\{.*?\}").unwrap();
+    let Expansion {
+        values,
+        bindings,
+        checks,
+        parts,
+        literals,
+        literal_len,
+        placeholders,
+        uses,
+    } = match expand_format(
+        &format_string,
+        &args,
+        &named_args,
+        &Ident::new("logger", Span::call_site()),
+    ) {
+        Ok(expansion) => expansion,
+        Err(error) => return error.to_compile_error().into(),
+    };
 
-    let placeholders: Vec<_> = placeholder_regex
-        .find_iter(&parsed_string)
-        .map(|m| m.as_str())
-        .collect();
+    if values.is_empty() {
+        return TokenStream::from(
+            quote! {pinocchio_log::logger::log_message(#literals.as_bytes());},
+        );
+    }
+
+    let buffer_len = match buffer_len {
+        Some(buffer_len) => buffer_len,
+        None => {
+            return computed_buffer_log(
+                &values,
+                &bindings,
+                &uses,
+                &checks,
+                &parts,
+                literal_len,
+                &placeholders,
+            )
+        }
+    };
+
+    let logger = if multiline {
+        quote! { pinocchio_log::logger::MultilineLogger::<#buffer_len> }
+    } else {
+        quote! { pinocchio_log::logger::Logger::<#buffer_len> }
+    };
+
+    // The arguments are evaluated before the logger is created, matching the
+    // evaluation order of `format_args!`.
+    TokenStream::from(quote! {
+        match (#(#values,)*) {
+            (#(#bindings,)*) => {
+                #(#checks;)*
+                let mut logger = #logger::default();
+                #(#parts;)*
+                logger.log();
+            }
+        }
+    })
+}
+
+/// Companion `format_into!` macro for `pinocchio-log`.
+///
+/// The macro formats a message into a caller provided buffer and returns the written
+/// part of the buffer as a `&[u8]`, without logging it. This can be used to build memos,
+/// return data or seeds without allocations. The buffer can be any value that provides
+/// a mutable `[MaybeUninit<u8>]` slice (e.g., `[MaybeUninit<u8>; N]` or
+/// `&mut [MaybeUninit<u8>]`), so it does not need to be initialized.
+///
+/// The format string and arguments follow the same syntax as the `log!` macro. When the
+/// buffer is not large enough, the message is truncated and the last byte of the buffer
+/// set to `@`, as with a `Logger`.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let mut buffer = [core::mem::MaybeUninit::<u8>::uninit(); 32];
+/// let seed = pinocchio_log::format_into!(buffer, "vault-{}", index);
+/// ```
+#[proc_macro]
+pub fn format_into(input: TokenStream) -> TokenStream {
+    let FormatIntoArgs {
+        buffer,
+        format_string,
+        args,
+        named_args,
+    } = parse_macro_input!(input as FormatIntoArgs);
+
+    let formatter = Ident::new("formatter", Span::mixed_site());
+
+    let Expansion {
+        values,
+        bindings,
+        checks,
+        parts,
+        ..
+    } = match expand_format(&format_string, &args, &named_args, &formatter) {
+        Ok(expansion) => expansion,
+        Err(error) => return error.to_compile_error().into(),
+    };
+
+    // The buffer is evaluated first, matching the evaluation order of `write!`.
+    let write = if values.is_empty() {
+        quote! { #(#parts;)* }
+    } else {
+        quote! {
+            match (#(#values,)*) {
+                (#(#bindings,)*) => {
+                    #(#checks;)*
+                    #(#parts;)*
+                }
+            }
+        }
+    };
+
+    TokenStream::from(quote! {
+        {
+            let mut #formatter = pinocchio_log::logger::Formatter::new((#buffer).as_mut());
+            #write
+            #formatter.finish()
+        }
+    })
+}
+
+/// Arguments and calls generated from a format string.
+struct Expansion {
+    /// The argument values, including the identifiers captured from the format string.
+    values: Vec<TokenStream2>,
+
+    /// The variables each argument value is bound to.
+    bindings: Vec<Ident>,
+
+    /// Compile time checks of the arguments.
+    checks: Vec<TokenStream2>,
+
+    /// The calls that append each part of the format string to the receiver.
+    parts: Vec<TokenStream2>,
+
+    /// The literal parts of the format string.
+    literals: String,
+
+    /// Total length of the literal parts.
+    literal_len: usize,
+
+    /// Index of the argument and formatting arguments of each placeholder.
+    placeholders: Vec<(usize, Vec<TokenStream2>)>,
+
+    /// Number of placeholders that reference each argument.
+    uses: Vec<usize>,
+}
+
+/// Parses the format string and resolves its placeholders to the arguments.
+///
+/// The generated calls append the parts of the format string to the variable named
+/// `receiver`, which can be either a `Logger` or a `Formatter`.
+fn expand_format(
+    format_string: &LitStr,
+    args: &[Expr],
+    named_args: &[(Ident, Expr)],
+    receiver: &Ident,
+) -> syn::Result<Expansion> {
+    let pieces = match parse_format_string(&format_string.value()) {
+        Ok(pieces) => pieces,
+        Err(message) => return Err(Error::new_spanned(format_string, message)),
+    };
+
+    // Values and spans of all arguments: positional arguments are followed by named arguments
+    // and identifiers captured from the format string.
+    let mut values: Vec<TokenStream2> = args.iter().map(|arg| quote! { #arg }).collect();
+    let mut spans: Vec<Span> = args.iter().map(|arg| arg.span()).collect();
+    let mut names: Vec<String> = Vec::new();
+
+    for (name, value) in named_args {
+        values.push(quote! { #value });
+        spans.push(value.span());
+        names.push(name.to_string());
+    }
+
+    // Number of arguments that can be referenced by position.
+    let positional = values.len();
+
+    let mut implicit = 0;
+    let mut explicit = false;
+    // First positional reference without a corresponding argument.
+    let mut invalid = None;
+    // Placeholders resolved to the index of their argument.
+    let mut resolved = Vec::new();
+
+    for piece in pieces {
+        match piece {
+            Piece::Literal(literal) => resolved.push((Some(literal), None)),
+            Piece::Placeholder(argument, spec) => {
+                let index = match argument {
+                    ArgumentRef::Next | ArgumentRef::Index(_) => {
+                        let index = if let ArgumentRef::Index(index) = argument {
+                            explicit = true;
+                            index
+                        } else {
+                            implicit += 1;
+                            implicit - 1
+                        };
+
+                        if index >= positional {
+                            invalid.get_or_insert(index);
+                            continue;
+                        }
+
+                        index
+                    }
+                    ArgumentRef::Name(name) => {
+                        if let Some(position) = names.iter().position(|n| *n == name) {
+                            args.len() + position
+                        } else {
+                            // Captures the identifier from the scope of the macro call.
+                            let ident = Ident::new(&name, format_string.span());
+                            values.push(quote! { #ident });
+                            spans.push(format_string.span());
+                            names.push(name);
+                            values.len() - 1
+                        }
+                    }
+                };
+
+                resolved.push((None, Some((index, spec))));
+            }
+        }
+    }
 
     // Check if there is an argument for each `{}` placeholder.
-    if placeholders.len() != args.len() {
+    if !explicit && named_args.is_empty() && implicit != args.len() {
         let arg_message = if args.is_empty() {
             "but no arguments were given".to_string()
         } else {
@@ -114,126 +783,200 @@ pub fn log(input: TokenStream) -> TokenStream {
             )
         };
 
-        return Error::new_spanned(
+        return Err(Error::new_spanned(
             format_string,
             format!(
                 "{} positional arguments in format string, {}",
-                placeholders.len(),
-                arg_message
+                implicit, arg_message
             ),
-        )
-        .to_compile_error()
-        .into();
+        ));
     }
 
-    if !placeholders.is_empty() {
-        // The parts of the format string with the placeholders replaced by arguments.
-        let mut replaced_parts = Vec::new();
+    if let Some(index) = invalid {
+        return Err(Error::new_spanned(
+            format_string,
+            format!(
+                "invalid reference to positional argument {} ({})",
+                index,
+                match positional {
+                    0 => "no arguments were given".to_string(),
+                    1 => "there is 1 argument".to_string(),
+                    count => format!("there are {} arguments", count),
+                }
+            ),
+        ));
+    }
 
-        let parts: Vec<&str> = placeholder_regex.split(&parsed_string).collect();
-        let part_iter = parts.iter();
+    let mut used = vec![false; values.len()];
 
-        let mut arg_iter = args.iter();
-        let mut ph_iter = placeholders.iter();
+    for (_, placeholder) in &resolved {
+        if let Some((index, _)) = placeholder {
+            used[*index] = true;
+        }
+    }
 
-        // Replace each occurrence of `{}` with their corresponding argument value.
-        for part in part_iter {
-            if !part.is_empty() {
-                replaced_parts.push(quote! { logger.append(#part) });
-            }
+    if let Some(unused) = used.iter().position(|used| !used) {
+        let message = if unused < args.len() {
+            "argument never used"
+        } else {
+            "named argument never used"
+        };
+        return Err(Error::new(spans[unused], message));
+    }
 
-            if let Some(arg) = arg_iter.next() {
-                // The number of placeholders was validated to be the same as
-                // the number of arguments, so this should never panic.
-                let placeholder = ph_iter.next().unwrap();
+    // Each argument is bound to a variable, so it is evaluated only once.
+    let bindings: Vec<Ident> = (0..values.len())
+        .map(|index| Ident::new(&format!("__arg{}", index), Span::mixed_site()))
+        .collect();
 
-                match *placeholder {
-                    "{}" => {
-                        replaced_parts.push(quote! { logger.append(#arg) });
-                    }
-                    value if value.starts_with("{:.") => {
-                        let precision =
-                            if let Ok(precision) = value[3..value.len() - 1].parse::<u8>() {
-                                precision
-                            } else {
-                                return Error::new_spanned(
-                                    format_string,
-                                    format!("invalid precision format: {}", value),
-                                )
-                                .to_compile_error()
-                                .into();
-                            };
-
-                        replaced_parts.push(quote! {
-                            logger.append_with_args(
-                                #arg,
-                                &[pinocchio_log::logger::Argument::Precision(#precision)]
-                            )
-                        });
-                    }
-                    value if value.starts_with("{:<.") || value.starts_with("{:>.") => {
-                        let size = if let Ok(size) = value[4..value.len() - 1].parse::<usize>() {
-                            size
-                        } else {
-                            return Error::new_spanned(
-                                format_string,
-                                format!("invalid truncate size format: {}", value),
-                            )
-                            .to_compile_error()
-                            .into();
-                        };
+    // The parts of the format string with the placeholders replaced by arguments.
+    let mut parts = Vec::new();
+    // The literal parts of the format string.
+    let mut literals = String::new();
+    // Compile time checks of the arguments.
+    let mut checks = Vec::new();
+    // Total length of the literal parts.
+    let mut literal_len = 0;
+    // Formatting arguments of each placeholder, used to compute the buffer length.
+    let mut placeholders = Vec::new();
+    // Number of placeholders that reference each argument.
+    let mut uses = vec![0; values.len()];
 
-                        match value.chars().nth(2) {
-                            Some('<') => {
-                                replaced_parts.push(quote! {
-                                    logger.append_with_args(
-                                        #arg,
-                                        &[pinocchio_log::logger::Argument::TruncateStart(#size)]
-                                    )
-                                });
-                            }
-                            Some('>') => {
-                                replaced_parts.push(quote! {
-                                    logger.append_with_args(
-                                        #arg,
-                                        &[pinocchio_log::logger::Argument::TruncateEnd(#size)]
-                                    )
-                                });
-                            }
-                            _ => {
-                                // This should not happen since we already checked the format.
-                                return Error::new_spanned(
-                                    format_string,
-                                    format!("invalid truncate format: {}", value),
-                                )
-                                .to_compile_error()
-                                .into();
-                            }
-                        }
-                    }
-                    _ => {
-                        return Error::new_spanned(
-                            format_string,
-                            format!("invalid placeholder: {}", placeholder),
-                        )
-                        .to_compile_error()
-                        .into();
-                    }
-                }
+    for (literal, placeholder) in resolved {
+        if let Some(literal) = literal {
+            literal_len += literal.len();
+            parts.push(quote! { #receiver.append(#literal) });
+            literals.push_str(&literal);
+        }
+
+        if let Some((index, spec)) = placeholder {
+            let binding = &bindings[index];
+
+            // Numeric specifiers are validated at compile time by requiring the
+            // argument to implement the `Numeric` trait, or the `Hex` trait for
+            // the hexadecimal format.
+            if spec.is_hex() {
+                checks.push(quote_spanned! { spans[index]=>
+                    pinocchio_log::logger::hex(&#binding)
+                });
+            } else if spec.is_numeric() {
+                checks.push(quote_spanned! { spans[index]=>
+                    pinocchio_log::logger::numeric(&#binding)
+                });
             }
+
+            let arguments = spec.arguments();
+
+            if arguments.is_empty() {
+                parts.push(quote! { #receiver.append(#binding) });
+            } else {
+                parts.push(quote! {
+                    #receiver.append_with_args(#binding, &[#(#arguments),*])
+                });
+            }
+
+            uses[index] += 1;
+            placeholders.push((index, arguments));
         }
+    }
 
-        // Generate the output string as a compile-time constant
-        TokenStream::from(quote! {
-            {
-                let mut logger = pinocchio_log::logger::Logger::<#buffer_len>::default();
-                #(#replaced_parts;)*
-                logger.log();
+    Ok(Expansion {
+        values,
+        bindings,
+        checks,
+        parts,
+        literals,
+        literal_len,
+        placeholders,
+        uses,
+    })
+}
+
+/// Generates the `log!` code when the buffer length is computed at compile time.
+///
+/// The length of a `Logger` cannot depend on the types of the arguments, so the message
+/// is formatted by a function generic over the buffer length, which is called with the
+/// smallest of the `COMPUTED_BUFFER_SIZES` that fits the message. The required length is
+/// computed in a `const` block, so the selection of the buffer length is optimized away.
+fn computed_buffer_log(
+    values: &[TokenStream2],
+    bindings: &[Ident],
+    uses: &[usize],
+    checks: &[TokenStream2],
+    parts: &[TokenStream2],
+    literal_len: usize,
+    placeholders: &[(usize, Vec<TokenStream2>)],
+) -> TokenStream {
+    let types: Vec<Ident> = (0..values.len())
+        .map(|index| Ident::new(&format!("__Arg{}", index), Span::mixed_site()))
+        .collect();
+    // Arguments referenced more than once must be `Copy`, as in the
+    // non-generic version of the macro.
+    let bounds: Vec<TokenStream2> = uses
+        .iter()
+        .map(|uses| {
+            if *uses > 1 {
+                quote! { pinocchio_log::logger::Log + Copy }
+            } else {
+                quote! { pinocchio_log::logger::Log }
             }
         })
-    } else {
-        TokenStream::from(quote! {pinocchio_log::logger::log_message(#format_string.as_bytes());})
-    }
+        .collect();
+    let lengths = placeholders.iter().map(|(index, arguments)| {
+        let ty = &types[*index];
+        quote! {
+            pinocchio_log::logger::max_len(
+                <#ty as pinocchio_log::logger::Log>::MAX_LEN,
+                &[#(#arguments),*]
+            )
+        }
+    });
+
+    let log_fn = Ident::new("__log", Span::mixed_site());
+    let len_fn = Ident::new("__len", Span::mixed_site());
+    let max = quote! { pinocchio_log::logger::MAX_COMPUTED_BUFFER_LEN };
+
+    // The types of the arguments are inferred.
+    let inferred = types.iter().map(|_| quote! { _ }).collect::<Vec<_>>();
+
+    let mut lower = 0;
+    let arms = COMPUTED_BUFFER_SIZES.iter().map(|size| {
+        let range = quote! { #lower..=#size };
+        lower = size + 1;
+        quote! { #range => #log_fn::<#size, #(#inferred),*>(#(#bindings),*) }
+    });
+
+    TokenStream::from(quote! {
+        match (#(#values,)*) {
+            (#(#bindings,)*) => {
+                #(#checks;)*
+
+                #[inline(always)]
+                fn #log_fn<const BUFFER: usize, #(#types: #bounds),*>(#(#bindings: #types),*) {
+                    let mut logger = pinocchio_log::logger::Logger::<BUFFER>::default();
+                    #(#parts;)*
+                    logger.log();
+                }
+
+                #[inline(always)]
+                fn #len_fn<#(#types: pinocchio_log::logger::Log),*>(#(_: &#types),*) -> usize {
+                    const {
+                        let length = #literal_len #(+ #lengths)*;
+                        assert!(
+                            length <= #max,
+                            "the computed buffer length exceeds `MAX_COMPUTED_BUFFER_LEN`"
+                        );
+                        length
+                    }
+                }
+
+                match #len_fn(#(&#bindings),*) {
+                    #(#arms,)*
+                    _ => #log_fn::<{ #max }, #(#inferred),*>(#(#bindings),*),
+                }
+            }
+        }
+    })
 }
 
 /// Attribute macro for instrumenting functions with compute unit logging.
@@ -291,3 +1034,235 @@ pub fn log_cu_usage(_attr: TokenStream, item: TokenStream) -> TokenStream {
 
     quote!(#input).into()
 }
+
+/// Options of a field specified with the `#[log(...)]` attribute.
+#[derive(Default)]
+struct FieldOptions {
+    /// Whether the field is omitted from the output.
+    skip: bool,
+
+    /// Number of decimal places for numeric values.
+    precision: Option<u8>,
+}
+
+impl FieldOptions {
+    /// Parses the `#[log(...)]` attributes of a field.
+    fn parse(field: &Field) -> syn::Result<Self> {
+        let mut options = FieldOptions::default();
+
+        for attribute in field.attrs.iter().filter(|attr| attr.path.is_ident("log")) {
+            let list = match attribute.parse_meta()? {
+                Meta::List(list) => list,
+                meta => {
+                    return Err(Error::new_spanned(
+                        meta,
+                        "expected `#[log(skip)]` or `#[log(precision = N)]`",
+                    ))
+                }
+            };
+
+            for nested in list.nested {
+                match nested {
+                    NestedMeta::Meta(Meta::Path(path)) if path.is_ident("skip") => {
+                        options.skip = true;
+                    }
+                    NestedMeta::Meta(Meta::NameValue(value))
+                        if value.path.is_ident("precision") =>
+                    {
+                        options.precision = Some(match &value.lit {
+                            Lit::Int(precision) => precision.base10_parse()?,
+                            lit => {
+                                return Err(Error::new_spanned(
+                                    lit,
+                                    "expected an integer precision",
+                                ))
+                            }
+                        });
+                    }
+                    nested => {
+                        return Err(Error::new_spanned(
+                            nested,
+                            "unknown log attribute, expected `skip` or `precision = N`",
+                        ))
+                    }
+                }
+            }
+        }
+
+        Ok(options)
+    }
+}
+
+/// Generates the statements to write the fields of a struct.
+fn derive_struct_fields(name: &Ident, fields: &Fields) -> syn::Result<Vec<TokenStream2>> {
+    let (named, open, close) = match fields {
+        Fields::Named(_) => (true, " { ", " }"),
+        Fields::Unnamed(_) => (false, "(", ")"),
+        Fields::Unit => (false, "", ""),
+    };
+
+    let mut statements = Vec::new();
+    let mut separator = format!("{}{}", name, open);
+    let mut skipped = false;
+    let mut written = false;
+
+    for (index, field) in fields.iter().enumerate() {
+        let options = FieldOptions::parse(field)?;
+
+        if options.skip {
+            skipped = true;
+            continue;
+        }
+
+        let member = match &field.ident {
+            Some(ident) => {
+                separator.push_str(&format!("{}: ", ident));
+                quote! { #ident }
+            }
+            None => {
+                let index = syn::Index::from(index);
+                quote! { #index }
+            }
+        };
+
+        let arguments = options
+            .precision
+            .map(|precision| quote! { pinocchio_log::logger::Argument::Precision(#precision) });
+
+        // The double reference gives precedence to implementations for references, so
+        // arrays and `str` fields are written the same way as when logged directly.
+        let write = quote_spanned! { field.ty.span()=>
+            (&&self.#member).debug_with_args(buffer, &[#arguments])
+        };
+
+        statements.push(quote! { writer.write(#separator.as_bytes()) });
+        statements.push(quote! {
+            // SAFETY: `Log` implementations return the number of bytes written.
+            unsafe { writer.write_with(|buffer| #write) }
+        });
+
+        separator = ", ".to_string();
+        written = true;
+    }
+
+    // Skipped fields are indicated with `..` on structs with named fields.
+    let end = match (written, skipped && named) {
+        (false, false) => name.to_string(),
+        (false, true) => format!("{} {{ .. }}", name),
+        (true, false) => close.to_string(),
+        (true, true) => format!(", ..{}", close),
+    };
+
+    statements.push(quote! { writer.write(#end.as_bytes()) });
+
+    Ok(statements)
+}
+
+/// Derive macro for the `Log` trait.
+///
+/// Structs are written as `Name { field: value, .. }`, where field values are written
+/// as when logged in a slice (e.g., `str` values are quoted), and enums are written as
+/// the name of their variant.
+///
+/// # Field attributes
+///
+/// - `#[log(skip)]`: omits the field from the output.
+/// - `#[log(precision = N)]`: writes a numeric field with `N` decimal places.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use pinocchio_log::{log, Log};
+///
+/// #[derive(Log)]
+/// struct Transfer {
+///     #[log(precision = 9)]
+///     lamports: u64,
+///     #[log(skip)]
+///     bump: u8,
+/// }
+///
+/// let transfer = Transfer { lamports: 1_500_000_000, bump: 255 };
+/// // log message: "Transfer { lamports: 1.500000000, .. }"
+/// log!("{}", transfer);
+/// ```
+#[proc_macro_derive(Log, attributes(log))]
+pub fn derive_log(input: TokenStream) -> TokenStream {
+    let mut input = parse_macro_input!(input as DeriveInput);
+    let name = &input.ident;
+
+    let statements = match &input.data {
+        Data::Struct(data) => derive_struct_fields(name, &data.fields),
+        Data::Enum(data) => data
+            .variants
+            .iter()
+            .find_map(|variant| {
+                variant
+                    .fields
+                    .iter()
+                    .find(|field| field.attrs.iter().any(|attr| attr.path.is_ident("log")))
+            })
+            .map_or_else(
+                || {
+                    let variants = data.variants.iter().map(|variant| {
+                        let ident = &variant.ident;
+                        let value = ident.to_string();
+                        quote! { Self::#ident { .. } => writer.write(#value.as_bytes()) }
+                    });
+                    Ok(alloc::vec![quote! { match *self { #(#variants,)* } }])
+                },
+                |field| {
+                    Err(Error::new_spanned(
+                        field,
+                        "`#[log]` attributes are not supported on enum variants",
+                    ))
+                },
+            ),
+        Data::Union(data) => Err(Error::new_spanned(
+            data.union_token,
+            "`Log` cannot be derived for unions",
+        )),
+    };
+
+    let statements = match statements {
+        Ok(statements) => statements,
+        Err(error) => return error.to_compile_error().into(),
+    };
+
+    // Type parameters must implement `Log` to be written.
+    let type_params: Vec<Ident> = input
+        .generics
+        .type_params()
+        .map(|param| param.ident.clone())
+        .collect();
+    let where_clause = input.generics.make_where_clause();
+
+    for param in type_params {
+        where_clause
+            .predicates
+            .push(parse_quote! { #param: pinocchio_log::logger::Log });
+    }
+
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    TokenStream::from(quote! {
+        #[automatically_derived]
+        unsafe impl #impl_generics pinocchio_log::logger::Log for #name #ty_generics #where_clause {
+            // Enums without variants never reach the end of the function.
+            #[allow(unreachable_code)]
+            #[inline]
+            fn write_with_args(
+                &self,
+                buffer: &mut [core::mem::MaybeUninit<u8>],
+                _args: &[pinocchio_log::logger::Argument],
+            ) -> usize {
+                #[allow(unused_imports)]
+                use pinocchio_log::logger::Log as _;
+
+                let mut writer = pinocchio_log::logger::BufferWriter::new(buffer);
+                #(#statements;)*
+                writer.finish()
+            }
+        }
+    })
+}
@@ -169,6 +169,16 @@ impl AccountInfo {
         unsafe { (*self.raw).resize_delta }
     }
 
+    /// Returns the data length of the account at the start of the instruction.
+    ///
+    /// The account data can grow by up to [`MAX_PERMITTED_DATA_INCREASE`] bytes
+    /// over this length within an instruction.
+    #[inline(always)]
+    pub fn original_data_len(&self) -> usize {
+        // Account length is always `< i32::MAX`.
+        (self.data_len() as i32 - self.resize_delta()) as usize
+    }
+
     /// Returns the lamports in the account.
     #[inline(always)]
     pub fn lamports(&self) -> u64 {
@@ -456,18 +466,16 @@ impl AccountInfo {
         Ok(())
     }
 
-    /// Realloc (either truncating or zero extending) the account's data.
+    /// Realloc (either truncating or extending) the account's data.
     ///
     /// The account data can be increased by up to [`MAX_PERMITTED_DATA_INCREASE`] bytes
     /// within an instruction.
     ///
     /// # Important
     ///
-    /// The use of the `zero_init` parameter, which indicated whether the newly
-    /// allocated memory should be zero-initialized or not, is now deprecated and
-    /// ignored. The method will always zero-initialize the newly allocated memory
-    /// if the new length is larger than the current data length. This is the same
-    /// behavior as [`Self::resize`].
+    /// The `zero_init` parameter indicates whether the newly allocated memory should be
+    /// zero-initialized or not, which is the same as calling [`Self::resize_zeroed`] or
+    /// [`Self::resize_uninit`], respectively.
     ///
     /// This method makes assumptions about the layout and location of memory
     /// referenced by `AccountInfo` fields. It should only be called for
@@ -475,14 +483,17 @@ impl AccountInfo {
     /// in the `process_instruction` entrypoint of a program.
     #[deprecated(since = "0.9.0", note = "Use AccountInfo::resize() instead")]
     #[inline(always)]
-    pub fn realloc(&self, new_len: usize, _zero_init: bool) -> Result<(), ProgramError> {
-        self.resize(new_len)
+    pub fn realloc(&self, new_len: usize, zero_init: bool) -> Result<(), ProgramError> {
+        if zero_init {
+            self.resize_zeroed(new_len)
+        } else {
+            self.resize_uninit(new_len)
+        }
     }
 
     /// Resize (either truncating or zero extending) the account's data.
     ///
-    /// The account data can be increased by up to [`MAX_PERMITTED_DATA_INCREASE`] bytes
-    /// within an instruction.
+    /// This is the same as [`Self::resize_zeroed`].
     ///
     /// # Important
     ///
@@ -490,50 +501,94 @@ impl AccountInfo {
     /// referenced by `AccountInfo` fields. It should only be called for
     /// instances of `AccountInfo` that were created by the runtime and received
     /// in the `process_instruction` entrypoint of a program.
-    #[inline]
+    #[inline(always)]
     pub fn resize(&self, new_len: usize) -> Result<(), ProgramError> {
+        self.resize_zeroed(new_len)
+    }
+
+    /// Resize (either truncating or zero extending) the account's data.
+    ///
+    /// When the data length increases, all bytes after the current data length are
+    /// zeroed, including bytes that were previously part of the account data if the
+    /// account was truncated earlier in the instruction.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ProgramError::AccountBorrowFailed`] error if the account data is
+    /// borrowed, or a [`ProgramError::InvalidRealloc`] error if the new length exceeds
+    /// the [`Self::original_data_len`] by more than [`MAX_PERMITTED_DATA_INCREASE`] bytes.
+    ///
+    /// # Important
+    ///
+    /// This method makes assumptions about the layout and location of memory
+    /// referenced by `AccountInfo` fields. It should only be called for
+    /// instances of `AccountInfo` that were created by the runtime and received
+    /// in the `process_instruction` entrypoint of a program.
+    #[inline]
+    pub fn resize_zeroed(&self, new_len: usize) -> Result<(), ProgramError> {
+        let current_len = self.data_len();
+
+        self.resize_uninit(new_len)?;
+
+        if new_len > current_len {
+            unsafe {
+                #[cfg(target_os = "solana")]
+                sol_memset_(
+                    self.data_ptr().add(current_len),
+                    0,
+                    (new_len - current_len) as u64,
+                );
+                #[cfg(not(target_os = "solana"))]
+                core::ptr::write_bytes(self.data_ptr().add(current_len), 0, new_len - current_len);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Resize (either truncating or extending) the account's data without
+    /// initializing the new bytes.
+    ///
+    /// When the data length increases, the content of the bytes after the current data
+    /// length is unspecified, since they might hold data written earlier in the
+    /// instruction before the account was truncated. This avoids the cost of zeroing
+    /// the data when the program is going to overwrite it.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ProgramError::AccountBorrowFailed`] error if the account data is
+    /// borrowed, or a [`ProgramError::InvalidRealloc`] error if the new length exceeds
+    /// the [`Self::original_data_len`] by more than [`MAX_PERMITTED_DATA_INCREASE`] bytes.
+    ///
+    /// # Important
+    ///
+    /// This method makes assumptions about the layout and location of memory
+    /// referenced by `AccountInfo` fields. It should only be called for
+    /// instances of `AccountInfo` that were created by the runtime and received
+    /// in the `process_instruction` entrypoint of a program.
+    #[inline]
+    pub fn resize_uninit(&self, new_len: usize) -> Result<(), ProgramError> {
         // Check wheather the account data is already borrowed.
         self.can_borrow_mut_data()?;
 
-        // Account length is always `< i32::MAX`...
-        let current_len = self.data_len() as i32;
-        // ...so the new length must fit in an `i32`.
-        let new_len = i32::try_from(new_len).map_err(|_| ProgramError::InvalidRealloc)?;
-
         // Return early if length hasn't changed.
-        if new_len == current_len {
+        if new_len == self.data_len() {
             return Ok(());
         }
 
-        let difference = new_len - current_len;
-        let accumulated_resize_delta = self.resize_delta() + difference;
+        let original_len = self.original_data_len();
 
         // Return an error when the length increase from the original serialized data
-        // length is too large and would result in an out of bounds allocation
-        if accumulated_resize_delta > MAX_PERMITTED_DATA_INCREASE as i32 {
+        // length is too large and would result in an out of bounds allocation.
+        if new_len.saturating_sub(original_len) > MAX_PERMITTED_DATA_INCREASE {
             return Err(ProgramError::InvalidRealloc);
         }
 
         unsafe {
             (*self.raw).data_len = new_len as u64;
-            (*self.raw).resize_delta = accumulated_resize_delta;
-        }
-
-        if difference > 0 {
-            unsafe {
-                #[cfg(target_os = "solana")]
-                sol_memset_(
-                    self.data_ptr().add(current_len as usize),
-                    0,
-                    difference as u64,
-                );
-                #[cfg(not(target_os = "solana"))]
-                core::ptr::write_bytes(
-                    self.data_ptr().add(current_len as usize),
-                    0,
-                    difference as usize,
-                );
-            }
+            // Account length is always `< i32::MAX` and the new length is at most
+            // `MAX_PERMITTED_DATA_INCREASE` bytes larger.
+            (*self.raw).resize_delta = new_len as i32 - original_len as i32;
         }
 
         Ok(())
@@ -608,12 +663,102 @@ impl AccountInfo {
         sol_memset_(self.data_ptr().sub(48), 0, 48);
     }
 
+    /// Close the account, moving all its lamports to the `destination` account.
+    ///
+    /// Unlike [`Self::close`], this method leaves the account in its final state: the
+    /// lamports are transferred to the `destination` account, the data is zeroed and
+    /// truncated to zero length, and the account is reassigned to the System program.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ProgramError::AccountBorrowFailed`] error if the lamports or data of
+    /// the account, or the lamports of the `destination` account, are borrowed &mdash; this
+    /// includes the case where `destination` is the account being closed &mdash; or a
+    /// [`ProgramError::ArithmeticOverflow`] error if the `destination` lamports overflow.
+    ///
+    /// # Safety
+    ///
+    /// The account is reassigned with [`Self::assign`], so it is undefined behavior to
+    /// use this method while there is an active reference to the `owner` returned by
+    /// [`Self::owner`].
+    ///
+    /// # Important
+    ///
+    /// This method makes assumptions about the layout and location of memory
+    /// referenced by `AccountInfo` fields. It should only be called for
+    /// instances of `AccountInfo` that were created by the runtime and received
+    /// in the `process_instruction` entrypoint of a program.
+    #[inline]
+    pub unsafe fn close_to(&self, destination: &AccountInfo) -> ProgramResult {
+        // Make sure the data can be zeroed before moving any lamports.
+        self.can_borrow_mut_data()?;
+
+        transfer_lamports(self, destination, self.lamports())?;
+
+        // SAFETY: There are no active borrows on the account data.
+        unsafe {
+            #[cfg(target_os = "solana")]
+            sol_memset_(self.data_ptr(), 0, self.data_len() as u64);
+            #[cfg(not(target_os = "solana"))]
+            core::ptr::write_bytes(self.data_ptr(), 0, self.data_len());
+        }
+
+        self.resize_uninit(0)?;
+
+        // SAFETY: The caller guarantees that there are no active references to the
+        // owner; the System program id is all zeros.
+        unsafe { self.assign(&[0; 32]) };
+
+        Ok(())
+    }
+
     /// Returns the memory address of the account data.
     fn data_ptr(&self) -> *mut u8 {
         unsafe { (self.raw as *mut u8).add(core::mem::size_of::<Account>()) }
     }
 }
 
+/// Transfer lamports between two accounts.
+///
+/// This moves lamports directly, without a CPI to the System program. The runtime only
+/// allows debiting lamports from writable accounts owned by the program, and crediting
+/// lamports to writable accounts.
+///
+/// # Errors
+///
+/// Returns a [`ProgramError::AccountBorrowFailed`] error if the lamports of either account
+/// are borrowed &mdash; this includes the case where `from` and `to` are the same account
+/// &mdash;, a [`ProgramError::InsufficientFunds`] error if `from` does not have enough
+/// lamports, or a [`ProgramError::ArithmeticOverflow`] error if the `to` lamports overflow.
+#[inline]
+pub fn transfer_lamports(from: &AccountInfo, to: &AccountInfo, amount: u64) -> ProgramResult {
+    let mut from_lamports = from.try_borrow_mut_lamports()?;
+    let mut to_lamports = to.try_borrow_mut_lamports()?;
+
+    let remaining = from_lamports
+        .checked_sub(amount)
+        .ok_or(ProgramError::InsufficientFunds)?;
+    *to_lamports = to_lamports
+        .checked_add(amount)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    *from_lamports = remaining;
+
+    Ok(())
+}
+
+/// Returns the total lamports of the accounts.
+///
+/// Duplicated accounts, which share the same underlying account, are counted once.
+#[inline]
+pub fn total_lamports(accounts: &[AccountInfo]) -> u128 {
+    accounts
+        .iter()
+        .enumerate()
+        .filter(|(i, account)| !accounts[..*i].iter().any(|seen| seen.raw == account.raw))
+        .map(|(_, account)| account.lamports() as u128)
+        .sum()
+}
+
 /// Number of bits of the [`Account::borrow_state`] flag to shift to get to
 /// the borrow state bits for lamports.
 ///   - `7 6 5 4 3 2 1 0`
@@ -1090,4 +1235,186 @@ mod tests {
         let data = account.try_borrow_data().unwrap();
         assert_eq!(data.len(), 500);
     }
+
+    #[test]
+    fn test_resize_zeroed_and_uninit() {
+        // 8-bytes aligned account data.
+        let mut data = [0u64; 100 * size_of::<u64>()];
+
+        // Set the borrow state.
+        data[0] = NOT_BORROWED as u64;
+        // Set the initial data length to 100.
+        //   - index `10` is equal to offset `10 * size_of::<u64>() = 80` bytes.
+        data[10] = 100;
+
+        let account = AccountInfo {
+            raw: data.as_mut_ptr() as *const _ as *mut Account,
+        };
+
+        account.try_borrow_mut_data().unwrap().fill(1);
+
+        // Truncating and growing with zero-initialization.
+
+        account.resize_zeroed(50).unwrap();
+        account.resize_zeroed(100).unwrap();
+
+        {
+            let data = account.try_borrow_data().unwrap();
+            assert!(data[..50].iter().all(|b| *b == 1));
+            assert!(data[50..].iter().all(|b| *b == 0));
+        }
+
+        // Truncating and growing without initialization keeps the
+        // previous values.
+
+        account.try_borrow_mut_data().unwrap().fill(1);
+        account.resize_uninit(50).unwrap();
+        account.resize_uninit(100).unwrap();
+
+        assert!(account.try_borrow_data().unwrap().iter().all(|b| *b == 1));
+
+        // Growth is limited relative to the original data length.
+
+        assert_eq!(account.original_data_len(), 100);
+
+        account.resize_uninit(0).unwrap();
+
+        assert_eq!(account.original_data_len(), 100);
+        assert!(account
+            .resize_uninit(100 + MAX_PERMITTED_DATA_INCREASE)
+            .is_ok());
+        assert_eq!(account.resize_delta(), MAX_PERMITTED_DATA_INCREASE as i32);
+        assert_eq!(
+            account.resize_uninit(101 + MAX_PERMITTED_DATA_INCREASE),
+            Err(ProgramError::InvalidRealloc)
+        );
+        assert_eq!(
+            account.resize_zeroed(usize::MAX),
+            Err(ProgramError::InvalidRealloc)
+        );
+
+        // Borrowed data cannot be resized.
+
+        let borrowed = account.try_borrow_data().unwrap();
+        assert_eq!(
+            account.resize_uninit(100),
+            Err(ProgramError::AccountBorrowFailed)
+        );
+        drop(borrowed);
+
+        account.resize(100).unwrap();
+        assert_eq!(account.resize_delta(), 0);
+    }
+
+    #[test]
+    fn test_transfer_lamports_and_close_to() {
+        // 8-bytes aligned account data.
+        let mut source_data = [0u64; 100 * size_of::<u64>()];
+        let mut destination_data = [0u64; 100 * size_of::<u64>()];
+
+        // Set the borrow state.
+        source_data[0] = NOT_BORROWED as u64;
+        destination_data[0] = NOT_BORROWED as u64;
+        // Set the owner.
+        //   - index `5` is equal to offset `5 * size_of::<u64>() = 40` bytes.
+        source_data[5] = u64::MAX;
+        // Set the lamports.
+        //   - index `9` is equal to offset `9 * size_of::<u64>() = 72` bytes.
+        source_data[9] = 1_000;
+        destination_data[9] = u64::MAX - 500;
+        // Set the initial data length to 100.
+        source_data[10] = 100;
+
+        let source = AccountInfo {
+            raw: source_data.as_mut_ptr() as *const _ as *mut Account,
+        };
+        let destination = AccountInfo {
+            raw: destination_data.as_mut_ptr() as *const _ as *mut Account,
+        };
+
+        let total = total_lamports(&[source.clone(), destination.clone()]);
+        assert_eq!(total, u64::MAX as u128 + 500);
+
+        transfer_lamports(&source, &destination, 400).unwrap();
+
+        assert_eq!(source.lamports(), 600);
+        assert_eq!(destination.lamports(), u64::MAX - 100);
+        assert_eq!(
+            total,
+            total_lamports(&[source.clone(), destination.clone()])
+        );
+
+        assert_eq!(
+            transfer_lamports(&source, &destination, 601),
+            Err(ProgramError::InsufficientFunds)
+        );
+        assert_eq!(
+            transfer_lamports(&source, &destination, 101),
+            Err(ProgramError::ArithmeticOverflow)
+        );
+        assert_eq!(
+            transfer_lamports(&source, &source, 1),
+            Err(ProgramError::AccountBorrowFailed)
+        );
+
+        // The destination cannot receive all the lamports.
+
+        // SAFETY: There are no active references to the owner.
+        assert_eq!(
+            unsafe { source.close_to(&destination) },
+            Err(ProgramError::ArithmeticOverflow)
+        );
+        assert_eq!(source.lamports(), 600);
+        assert_eq!(source.data_len(), 100);
+
+        // Close the account.
+
+        *destination.try_borrow_mut_lamports().unwrap() = 0;
+        source.try_borrow_mut_data().unwrap().fill(1);
+
+        // SAFETY: There are no active references to the owner.
+        unsafe { source.close_to(&destination) }.unwrap();
+
+        assert_eq!(source.lamports(), 0);
+        assert_eq!(destination.lamports(), 600);
+        assert_eq!(source.data_len(), 0);
+        assert_eq!(source.resize_delta(), -100);
+        assert_eq!(source.owner(), &[0; 32]);
+
+        source.resize_uninit(100).unwrap();
+        assert!(source.try_borrow_data().unwrap().iter().all(|b| *b == 0));
+    }
+
+    #[test]
+    fn test_total_lamports_with_duplicated_accounts() {
+        // 8-bytes aligned account data.
+        let mut source_data = [0u64; 100 * size_of::<u64>()];
+        let mut destination_data = [0u64; 100 * size_of::<u64>()];
+
+        // Set the borrow state.
+        source_data[0] = NOT_BORROWED as u64;
+        destination_data[0] = NOT_BORROWED as u64;
+        // Set the lamports.
+        //   - index `9` is equal to offset `9 * size_of::<u64>() = 72` bytes.
+        source_data[9] = 1_000;
+        destination_data[9] = 500;
+
+        let source = AccountInfo {
+            raw: source_data.as_mut_ptr() as *const _ as *mut Account,
+        };
+        let destination = AccountInfo {
+            raw: destination_data.as_mut_ptr() as *const _ as *mut Account,
+        };
+
+        // The destination is passed twice, sharing the same account.
+        let accounts = [source.clone(), destination.clone(), destination.clone()];
+
+        let total = total_lamports(&accounts);
+        assert_eq!(total, 1_500);
+
+        transfer_lamports(&accounts[0], &accounts[2], 400).unwrap();
+
+        assert_eq!(accounts[1].lamports(), 900);
+        assert_eq!(total, total_lamports(&accounts));
+    }
 }
@@ -4,7 +4,7 @@ use core::{mem::MaybeUninit, ops::Deref, slice::from_raw_parts};
 
 use crate::{
     account_info::{AccountInfo, BorrowState},
-    instruction::{Account, Instruction, Signer},
+    instruction::{Account, AccountMeta, Instruction, Signer},
     program_error::ProgramError,
     pubkey::Pubkey,
     ProgramResult,
@@ -250,6 +250,144 @@ pub fn slice_invoke_signed(
     }
 }
 
+/// Context to issue multiple cross-program invocations to the same program with a
+/// fixed set of accounts.
+///
+/// The `AccountInfo`s are converted into the [`Account`] representation expected by
+/// the runtime once, when the context is created, and reused by every invocation.
+/// Each invocation can use a different instruction data and any subset of the
+/// accounts of the context, in any order. Since the runtime looks up the accounts of
+/// the instruction by their key, all accounts of the context are passed to every
+/// invocation.
+///
+/// Before each invocation, the borrow state of the accounts referenced by the
+/// instruction is checked against their mutability, the same way as
+/// [`invoke_signed`] does.
+///
+/// # Example
+///
+/// ```ignore
+/// let mut context = CpiContext::new(&pinocchio_token::ID, [vault, authority, a, b]);
+///
+/// for (destination, amount) in [(a, 10), (b, 20)] {
+///     let mut data = [3; 9];
+///     data[1..].copy_from_slice(&u64::to_le_bytes(amount));
+///
+///     context.invoke_signed(
+///         &[
+///             AccountMeta::writable(vault.key()),
+///             AccountMeta::writable(destination.key()),
+///             AccountMeta::readonly_signer(authority.key()),
+///         ],
+///         &data,
+///         &[signer.clone()],
+///     )?;
+/// }
+/// ```
+pub struct CpiContext<'a, const ACCOUNTS: usize> {
+    /// Program to invoke.
+    program_id: &'a Pubkey,
+
+    /// Accounts of the context.
+    account_infos: [&'a AccountInfo; ACCOUNTS],
+
+    /// Accounts of the context in the representation expected by the runtime.
+    accounts: [Account<'a>; ACCOUNTS],
+}
+
+impl<'a, const ACCOUNTS: usize> CpiContext<'a, ACCOUNTS> {
+    /// Creates a new `CpiContext` for the given program and accounts.
+    #[inline(always)]
+    pub fn new(program_id: &'a Pubkey, account_infos: [&'a AccountInfo; ACCOUNTS]) -> Self {
+        const {
+            assert!(
+                ACCOUNTS <= MAX_CPI_ACCOUNTS,
+                "ACCOUNTS is greater than allowed MAX_CPI_ACCOUNTS"
+            );
+        }
+
+        Self {
+            program_id,
+            account_infos,
+            accounts: account_infos.map(Account::from),
+        }
+    }
+
+    /// Returns the program invoked by the context.
+    #[inline(always)]
+    pub fn program_id(&self) -> &'a Pubkey {
+        self.program_id
+    }
+
+    /// Returns the accounts of the context.
+    #[inline(always)]
+    pub fn account_infos(&self) -> &[&'a AccountInfo; ACCOUNTS] {
+        &self.account_infos
+    }
+
+    /// Invoke an instruction with the given accounts and data.
+    #[inline(always)]
+    pub fn invoke(&mut self, accounts: &[AccountMeta], data: &[u8]) -> ProgramResult {
+        self.invoke_signed(accounts, data, &[])
+    }
+
+    /// Invoke an instruction with the given accounts, data and signers.
+    ///
+    /// Returns a [`ProgramError::InvalidArgument`] error if any of the `accounts` is
+    /// not part of the context, and a [`ProgramError::AccountBorrowFailed`] error if
+    /// the borrow state of an account is not compatible with its mutability on the
+    /// instruction.
+    pub fn invoke_signed(
+        &mut self,
+        accounts: &[AccountMeta],
+        data: &[u8],
+        signers_seeds: &[Signer],
+    ) -> ProgramResult {
+        for account_meta in accounts {
+            let account_info = self
+                .account_infos
+                .iter()
+                .find(|account_info| {
+                    // Metas created from the account infos of the context share the
+                    // same key reference, which avoids comparing the keys.
+                    core::ptr::eq(account_info.key(), account_meta.pubkey)
+                        || account_info.key() == account_meta.pubkey
+                })
+                .ok_or(ProgramError::InvalidArgument)?;
+
+            let invalid_state = if account_meta.is_writable {
+                BorrowState::Borrowed
+            } else {
+                BorrowState::MutablyBorrowed
+            };
+
+            if account_info.is_borrowed(invalid_state) {
+                return Err(ProgramError::AccountBorrowFailed);
+            }
+        }
+
+        // Previous invocations might have changed the length of the accounts.
+        self.accounts
+            .iter_mut()
+            .zip(self.account_infos.iter())
+            .for_each(|(account, account_info)| account.sync_data_len(account_info));
+
+        let instruction = Instruction {
+            program_id: self.program_id,
+            data,
+            accounts,
+        };
+
+        // SAFETY: At this point it is guaranteed that account infos are borrowable
+        // according to their mutability on the instruction.
+        unsafe {
+            invoke_signed_unchecked(&instruction, &self.accounts, signers_seeds);
+        }
+
+        Ok(())
+    }
+}
+
 /// Internal function to invoke a cross-program instruction with signatures
 /// from a slice of `AccountInfo`s performing borrow checking.
 ///
@@ -514,6 +652,107 @@ pub fn get_return_data() -> Option<ReturnData> {
     core::hint::black_box(None)
 }
 
+/// Types that can be used as typed return data.
+///
+/// Return data values are copied to and from the return data buffer as raw bytes, in
+/// the native (little-endian) byte order.
+///
+/// # Safety
+///
+/// Implementors must have a fixed memory layout without padding bytes (e.g., primitive
+/// integers or `repr(C)` structs of them), and any bit pattern must be a valid value
+/// of the type.
+pub unsafe trait ReturnValue: Copy {}
+
+macro_rules! impl_return_value {
+    ( $($type:ty),* ) => {
+        $(
+            // SAFETY: Primitive integers have no padding and are valid for any bit pattern.
+            unsafe impl ReturnValue for $type {}
+        )*
+    };
+}
+
+impl_return_value!(u8, u16, u32, u64, u128, i8, i16, i32, i64, i128);
+
+// SAFETY: Arrays have no padding between elements.
+unsafe impl<T: ReturnValue, const N: usize> ReturnValue for [T; N] {}
+
+/// Set the running program's return data to the bytes of a value.
+///
+/// The caller can retrieve the value with [`invoke_and_get_return`].
+#[inline(always)]
+pub fn set_return_data_value<T: ReturnValue>(value: &T) {
+    const {
+        assert!(
+            core::mem::size_of::<T>() <= MAX_RETURN_DATA,
+            "size of the value is greater than MAX_RETURN_DATA"
+        );
+    }
+
+    set_return_data(as_bytes(value));
+}
+
+/// Invoke a cross-program instruction and return the value set as return data by
+/// the invoked program.
+///
+/// This function performs the invocation using [`slice_invoke_signed`]. After the
+/// invocation, it validates that the return data was set by the invoked program,
+/// i.e., its program id matches the `program_id` of the `instruction`, and decodes
+/// it into a value of type `T`.
+///
+/// Returns a [`ProgramError::IncorrectProgramId`] error if the return data was set by
+/// a different program, and a [`ProgramError::InvalidArgument`] error if no return
+/// data was set or its size does not match the size of `T`.
+#[inline]
+pub fn invoke_and_get_return<T: ReturnValue>(
+    instruction: &Instruction,
+    account_infos: &[&AccountInfo],
+    signers_seeds: &[Signer],
+) -> Result<T, ProgramError> {
+    slice_invoke_signed(instruction, account_infos, signers_seeds)?;
+
+    #[cfg(target_os = "solana")]
+    {
+        let mut value = MaybeUninit::<T>::uninit();
+        let mut program_id = MaybeUninit::<Pubkey>::uninit();
+
+        // The syscall copies at most `size_of::<T>()` bytes and returns the size of
+        // the return data.
+        let size = unsafe {
+            crate::syscalls::sol_get_return_data(
+                value.as_mut_ptr() as *mut u8,
+                core::mem::size_of::<T>() as u64,
+                program_id.as_mut_ptr(),
+            )
+        };
+
+        if size == 0 || size as usize != core::mem::size_of::<T>() {
+            return Err(ProgramError::InvalidArgument);
+        }
+
+        // SAFETY: The syscall initializes the program id when there is return data.
+        if unsafe { program_id.assume_init_ref() } != instruction.program_id {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        // SAFETY: The syscall initialized all bytes of the value and any bit pattern
+        // is a valid `T`.
+        Ok(unsafe { value.assume_init() })
+    }
+
+    #[cfg(not(target_os = "solana"))]
+    core::hint::black_box(Err(ProgramError::InvalidArgument))
+}
+
+/// Returns the bytes of a value.
+#[inline(always)]
+fn as_bytes<T: ReturnValue>(value: &T) -> &[u8] {
+    // SAFETY: `ReturnValue` types do not have padding bytes, so all bytes of the value
+    // are initialized.
+    unsafe { from_raw_parts(value as *const T as *const u8, core::mem::size_of::<T>()) }
+}
+
 /// Struct to hold the return data from an invoked program.
 pub struct ReturnData {
     /// Program that most recently set the return data.
@@ -545,3 +784,75 @@ impl Deref for ReturnData {
         self.as_slice()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::NON_DUP_MARKER as NOT_BORROWED;
+
+    #[test]
+    fn test_return_value_bytes() {
+        assert_eq!(as_bytes(&0x0102030405060708u64), &[8, 7, 6, 5, 4, 3, 2, 1]);
+        assert_eq!(as_bytes(&[1u16, 2]), &[1, 0, 2, 0]);
+        assert_eq!(as_bytes(&[7u8; 32]), &[7; 32]);
+    }
+
+    #[test]
+    fn test_cpi_context() {
+        let mut data = [crate::account_info::Account::default(); 3];
+
+        let [first, second, outsider] = [0, 1, 2].map(|i| {
+            let raw = &mut data[i] as *mut crate::account_info::Account;
+            // SAFETY: `raw` points to a valid `Account`; offset `8` is the `key` field.
+            unsafe {
+                (*raw).borrow_state = NOT_BORROWED;
+                (raw as *mut u8).add(8).write_bytes(i as u8, 32);
+            }
+            AccountInfo { raw }
+        });
+
+        let program_id = [9; 32];
+        let mut context = CpiContext::new(&program_id, [&first, &second]);
+
+        assert_eq!(context.program_id(), &program_id);
+
+        // Metas can reference any subset of the accounts, using keys from a
+        // different reference.
+        let key = [1; 32];
+        assert!(context
+            .invoke(&[AccountMeta::readonly(&key)], &[1, 2, 3])
+            .is_ok());
+        assert!(context
+            .invoke(
+                &[
+                    AccountMeta::writable(second.key()),
+                    AccountMeta::writable_signer(first.key()),
+                ],
+                &[]
+            )
+            .is_ok());
+
+        // Accounts must be part of the context.
+        assert_eq!(
+            context.invoke(&[AccountMeta::readonly(outsider.key())], &[]),
+            Err(ProgramError::InvalidArgument)
+        );
+
+        // Writable accounts cannot be borrowed.
+        let borrowed = first.try_borrow_data().unwrap();
+
+        assert!(context
+            .invoke(&[AccountMeta::readonly(first.key())], &[])
+            .is_ok());
+        assert_eq!(
+            context.invoke(&[AccountMeta::writable(first.key())], &[]),
+            Err(ProgramError::AccountBorrowFailed)
+        );
+
+        drop(borrowed);
+
+        assert!(context
+            .invoke(&[AccountMeta::writable(first.key())], &[])
+            .is_ok());
+    }
+}
@@ -0,0 +1,260 @@
+//! Compile-time check of whether an address is a point on the ed25519 curve.
+//!
+//! Field elements are represented as five 51-bit limbs, using the same
+//! algorithms as `curve25519-dalek` for point decompression.
+
+/// A field element modulo `2^255 - 19`.
+type FieldElement = [u64; 5];
+
+/// Mask of the lower 51 bits of a limb.
+const LOW_51_BITS: u64 = (1 << 51) - 1;
+
+/// Field element `1`.
+const ONE: FieldElement = [1, 0, 0, 0, 0];
+
+/// Edwards `d` curve parameter, equal to `-121665/121666`.
+const EDWARDS_D: FieldElement = [
+    929955233495203,
+    466365720129213,
+    1662059464998953,
+    2033849074728123,
+    1442794654840575,
+];
+
+/// Checks whether the bytes represent a compressed point on the ed25519 curve.
+///
+/// The bytes represent a point if the `y` coordinate they encode can be decompressed,
+/// i.e., `x^2 = (y^2 - 1) / (d * y^2 + 1)` has a solution. This is the same check
+/// performed by the runtime to validate program derived addresses.
+pub(crate) const fn is_on_curve(bytes: &[u8; 32]) -> bool {
+    let y = from_bytes(bytes);
+    let yy = square(&y);
+    let u = sub(&yy, &ONE);
+    let v = add(&mul(&yy, &EDWARDS_D), &ONE);
+
+    // r = (u * v^3) * (u * v^7)^((p - 5) / 8)
+    let v3 = mul(&square(&v), &v);
+    let v7 = mul(&square(&v3), &v);
+    let r = mul(&mul(&u, &v3), &pow_p58(&mul(&u, &v7)));
+
+    // `y` is valid when `v * r^2 = ±u`.
+    let check = to_bytes(&mul(&v, &square(&r)));
+
+    bytes_eq(&check, &to_bytes(&u)) || bytes_eq(&check, &to_bytes(&neg(&u)))
+}
+
+/// Loads a field element from its little-endian representation, ignoring the
+/// most significant bit.
+const fn from_bytes(bytes: &[u8; 32]) -> FieldElement {
+    const fn load8(bytes: &[u8; 32], offset: usize) -> u64 {
+        let mut value = 0;
+        let mut i = 0;
+
+        while i < 8 {
+            value |= (bytes[offset + i] as u64) << (8 * i);
+            i += 1;
+        }
+
+        value
+    }
+
+    [
+        load8(bytes, 0) & LOW_51_BITS,
+        (load8(bytes, 6) >> 3) & LOW_51_BITS,
+        (load8(bytes, 12) >> 6) & LOW_51_BITS,
+        (load8(bytes, 19) >> 1) & LOW_51_BITS,
+        (load8(bytes, 24) >> 12) & LOW_51_BITS,
+    ]
+}
+
+/// Returns the canonical little-endian representation of a field element.
+const fn to_bytes(element: &FieldElement) -> [u8; 32] {
+    let mut limbs = reduce(*element);
+
+    // Compute `q` such that `limbs - q * p` is the canonical value.
+    let mut q = (limbs[0] + 19) >> 51;
+    q = (limbs[1] + q) >> 51;
+    q = (limbs[2] + q) >> 51;
+    q = (limbs[3] + q) >> 51;
+    q = (limbs[4] + q) >> 51;
+
+    limbs[0] += 19 * q;
+
+    limbs[1] += limbs[0] >> 51;
+    limbs[0] &= LOW_51_BITS;
+    limbs[2] += limbs[1] >> 51;
+    limbs[1] &= LOW_51_BITS;
+    limbs[3] += limbs[2] >> 51;
+    limbs[2] &= LOW_51_BITS;
+    limbs[4] += limbs[3] >> 51;
+    limbs[3] &= LOW_51_BITS;
+    limbs[4] &= LOW_51_BITS;
+
+    let mut bytes = [0u8; 32];
+    let mut bit = 0;
+
+    while bit < 255 {
+        let limb = limbs[bit / 51] >> (bit % 51);
+        bytes[bit / 8] |= ((limb & 1) as u8) << (bit % 8);
+        bit += 1;
+    }
+
+    bytes
+}
+
+/// Carries the bits above `51` of each limb into the next limb.
+const fn reduce(mut limbs: FieldElement) -> FieldElement {
+    let c0 = limbs[0] >> 51;
+    let c1 = limbs[1] >> 51;
+    let c2 = limbs[2] >> 51;
+    let c3 = limbs[3] >> 51;
+    let c4 = limbs[4] >> 51;
+
+    limbs[0] &= LOW_51_BITS;
+    limbs[1] &= LOW_51_BITS;
+    limbs[2] &= LOW_51_BITS;
+    limbs[3] &= LOW_51_BITS;
+    limbs[4] &= LOW_51_BITS;
+
+    // `2^255 = 19 (mod p)`.
+    limbs[0] += c4 * 19;
+    limbs[1] += c0;
+    limbs[2] += c1;
+    limbs[3] += c2;
+    limbs[4] += c3;
+
+    limbs
+}
+
+const fn add(a: &FieldElement, b: &FieldElement) -> FieldElement {
+    reduce([
+        a[0] + b[0],
+        a[1] + b[1],
+        a[2] + b[2],
+        a[3] + b[3],
+        a[4] + b[4],
+    ])
+}
+
+const fn sub(a: &FieldElement, b: &FieldElement) -> FieldElement {
+    // Add `16 * p` to avoid underflow.
+    reduce([
+        (a[0] + 36028797018963664) - b[0],
+        (a[1] + 36028797018963952) - b[1],
+        (a[2] + 36028797018963952) - b[2],
+        (a[3] + 36028797018963952) - b[3],
+        (a[4] + 36028797018963952) - b[4],
+    ])
+}
+
+const fn neg(a: &FieldElement) -> FieldElement {
+    sub(&[0; 5], a)
+}
+
+const fn mul(a: &FieldElement, b: &FieldElement) -> FieldElement {
+    #[inline(always)]
+    const fn m(x: u64, y: u64) -> u128 {
+        (x as u128) * (y as u128)
+    }
+
+    let b1_19 = b[1] * 19;
+    let b2_19 = b[2] * 19;
+    let b3_19 = b[3] * 19;
+    let b4_19 = b[4] * 19;
+
+    let c0 = m(a[0], b[0]) + m(a[4], b1_19) + m(a[3], b2_19) + m(a[2], b3_19) + m(a[1], b4_19);
+    let mut c1 = m(a[1], b[0]) + m(a[0], b[1]) + m(a[4], b2_19) + m(a[3], b3_19) + m(a[2], b4_19);
+    let mut c2 = m(a[2], b[0]) + m(a[1], b[1]) + m(a[0], b[2]) + m(a[4], b3_19) + m(a[3], b4_19);
+    let mut c3 = m(a[3], b[0]) + m(a[2], b[1]) + m(a[1], b[2]) + m(a[0], b[3]) + m(a[4], b4_19);
+    let mut c4 = m(a[4], b[0]) + m(a[3], b[1]) + m(a[2], b[2]) + m(a[1], b[3]) + m(a[0], b[4]);
+
+    c1 += c0 >> 51;
+    c2 += c1 >> 51;
+    c3 += c2 >> 51;
+    c4 += c3 >> 51;
+
+    let mut limbs = [
+        (c0 as u64) & LOW_51_BITS,
+        (c1 as u64) & LOW_51_BITS,
+        (c2 as u64) & LOW_51_BITS,
+        (c3 as u64) & LOW_51_BITS,
+        (c4 as u64) & LOW_51_BITS,
+    ];
+
+    limbs[0] += ((c4 >> 51) as u64) * 19;
+    limbs[1] += limbs[0] >> 51;
+    limbs[0] &= LOW_51_BITS;
+
+    limbs
+}
+
+const fn square(a: &FieldElement) -> FieldElement {
+    mul(a, a)
+}
+
+/// Raises a field element to the power of `(p - 5) / 8 = 2^252 - 3`.
+const fn pow_p58(a: &FieldElement) -> FieldElement {
+    // The exponent has all its `252` bits set, apart from bit `1`.
+    let mut result = ONE;
+    let mut bit = 252;
+
+    while bit > 0 {
+        bit -= 1;
+        result = square(&result);
+
+        if bit != 1 {
+            result = mul(&result, a);
+        }
+    }
+
+    result
+}
+
+const fn bytes_eq(a: &[u8; 32], b: &[u8; 32]) -> bool {
+    let mut i = 0;
+
+    while i < 32 {
+        if a[i] != b[i] {
+            return false;
+        }
+        i += 1;
+    }
+
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_on_curve() {
+        // Compressed ed25519 base point.
+        let mut base_point = [0x66; 32];
+        base_point[0] = 0x58;
+
+        assert!(is_on_curve(&base_point));
+        // `y = 0` is on the curve, since `-1` has a square root.
+        assert!(is_on_curve(&[0; 32]));
+        // `y = 2` is not on the curve.
+        let mut two = [0; 32];
+        two[0] = 2;
+        assert!(!is_on_curve(&two));
+    }
+
+    #[test]
+    fn test_field_arithmetic() {
+        let mut bytes = [0xff; 32];
+        bytes[31] = 0x7f;
+        // `2^255 - 1 = 18 (mod p)`.
+        let mut expected = [0; 32];
+        expected[0] = 18;
+        assert_eq!(to_bytes(&from_bytes(&bytes)), expected);
+
+        let d = to_bytes(&EDWARDS_D);
+        // `d * 121666 = -121665`.
+        let product = mul(&EDWARDS_D, &[121666, 0, 0, 0, 0]);
+        assert_eq!(to_bytes(&product), to_bytes(&neg(&[121665, 0, 0, 0, 0])));
+        assert_eq!(to_bytes(&from_bytes(&d)), d);
+    }
+}
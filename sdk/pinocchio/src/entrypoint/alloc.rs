@@ -0,0 +1,651 @@
+//! Global allocators used as the rust heap when running programs.
+//!
+//! All allocators manage the memory region `[start, start + len)` and keep their
+//! state at the start of the region. They rely on the region being zero-initialized
+//! before the first allocation, which is the case for the program heap.
+
+extern crate alloc;
+
+use alloc::alloc::GlobalAlloc;
+use core::{
+    alloc::Layout,
+    mem::size_of,
+    ptr::{copy_nonoverlapping, null_mut},
+};
+
+use super::HeapUsage;
+
+/// The bump allocator used as the default rust heap when running programs.
+pub struct BumpAllocator {
+    pub start: usize,
+    pub len: usize,
+}
+
+impl BumpAllocator {
+    /// Returns the usage of the heap.
+    ///
+    /// Since the bump allocator does not free memory, the memory in use is
+    /// always the peak usage.
+    #[inline]
+    pub fn usage(&self) -> HeapUsage {
+        // The first bytes of the heap store the allocator position.
+        let reserved = self.start + size_of::<*mut u8>();
+        // SAFETY: The start of the heap region holds the allocator position.
+        let position = match unsafe { *(self.start as *const usize) } {
+            0 => self.start + self.len,
+            position => position,
+        };
+        let used = self.start + self.len - position;
+
+        HeapUsage {
+            used,
+            peak: used,
+            remaining: position.saturating_sub(reserved),
+        }
+    }
+}
+
+/// Integer arithmetic in this global allocator implementation is safe when
+/// operating on the prescribed [`HEAP_START_ADDRESS`] and [`HEAP_LENGTH`]. Any
+/// other use may overflow and is thus unsupported and at one's own risk.
+///
+/// [`HEAP_START_ADDRESS`]: super::HEAP_START_ADDRESS
+/// [`HEAP_LENGTH`]: super::HEAP_LENGTH
+#[allow(clippy::arithmetic_side_effects)]
+unsafe impl GlobalAlloc for BumpAllocator {
+    /// Allocates memory as a bump allocator.
+    #[inline]
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let pos_ptr = self.start as *mut usize;
+
+        let mut pos = *pos_ptr;
+        if pos == 0 {
+            // First time, set starting position.
+            pos = self.start + self.len;
+        }
+        pos = pos.saturating_sub(layout.size());
+        pos &= !(layout.align().wrapping_sub(1));
+        if pos < self.start + size_of::<*mut u8>() {
+            out_of_memory(layout.size());
+            return null_mut();
+        }
+        *pos_ptr = pos;
+        pos as *mut u8
+    }
+    #[inline]
+    unsafe fn dealloc(&self, _: *mut u8, _: Layout) {
+        // I'm a bump allocator, I don't free.
+    }
+}
+
+/// Position of the heap of a [`StackAllocator`] at a point in time.
+///
+/// A checkpoint is obtained with [`StackAllocator::checkpoint`] and can be used to
+/// release all memory allocated after it with [`StackAllocator::restore`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct HeapCheckpoint(usize);
+
+/// A bump allocator that reclaims the most recent allocation.
+///
+/// Memory is allocated from the start of the heap region towards its end. Since
+/// allocations are laid out in order, the most recent allocation is always at the
+/// end of the used memory. This allows the allocator to:
+///
+/// * reclaim the memory of the most recent allocation when it is deallocated;
+/// * grow or shrink the most recent allocation in place on `realloc`, which is the
+///   common case when pushing values to a `Vec`.
+///
+/// Deallocating any other allocation does not free memory, in the same way as the
+/// [`BumpAllocator`].
+///
+/// The allocator also supports resetting the heap to a [`HeapCheckpoint`], which
+/// releases all memory allocated after the checkpoint was taken.
+pub struct StackAllocator {
+    pub start: usize,
+    pub len: usize,
+}
+
+/// State of a [`StackAllocator`], stored at the start of the heap region.
+#[repr(C)]
+struct StackState {
+    /// Current position of the heap.
+    position: usize,
+
+    /// Highest position of the heap.
+    peak: usize,
+}
+
+impl StackAllocator {
+    /// Returns the usage of the heap.
+    #[inline]
+    pub fn usage(&self) -> HeapUsage {
+        let reserved = self.start + size_of::<StackState>();
+        // SAFETY: The start of the heap region holds the allocator state.
+        let (position, peak) = unsafe {
+            let state = &*(self.start as *const StackState);
+            (self.position(), core::cmp::max(state.peak, reserved))
+        };
+
+        HeapUsage {
+            used: position - reserved,
+            peak: peak - reserved,
+            remaining: self.start + self.len - position,
+        }
+    }
+
+    /// Returns a checkpoint of the current position of the heap.
+    #[inline(always)]
+    pub fn checkpoint(&self) -> HeapCheckpoint {
+        // SAFETY: The start of the heap region holds the allocator position.
+        HeapCheckpoint(unsafe { self.position() })
+    }
+
+    /// Resets the position of the heap to the given checkpoint, releasing all
+    /// memory allocated after the checkpoint was taken.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure that no value allocated after the checkpoint was
+    /// taken is used after this method is called, and that the checkpoint was
+    /// taken from this allocator.
+    #[inline(always)]
+    pub unsafe fn restore(&self, checkpoint: HeapCheckpoint) {
+        self.set_position(checkpoint.0);
+    }
+
+    /// Runs the closure `f` and releases all memory allocated while running it.
+    ///
+    /// This is useful to reuse heap memory for temporary values, e.g., when
+    /// processing each element of a list requires heap allocations that are no
+    /// longer needed once the element is processed.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure that no value allocated while running `f` outlives
+    /// the call, including through the value returned by `f`.
+    #[inline(always)]
+    pub unsafe fn scope<R>(&self, f: impl FnOnce() -> R) -> R {
+        let checkpoint = self.checkpoint();
+        let result = f();
+        self.restore(checkpoint);
+        result
+    }
+
+    /// Returns the current position of the heap.
+    ///
+    /// # Safety
+    ///
+    /// The start of the heap region must be valid for reads.
+    #[inline(always)]
+    unsafe fn position(&self) -> usize {
+        match (*(self.start as *const StackState)).position {
+            // First time, the position is right after the allocator state.
+            0 => self.start + size_of::<StackState>(),
+            position => position,
+        }
+    }
+
+    /// Updates the current position of the heap, keeping track of the
+    /// highest position.
+    ///
+    /// # Safety
+    ///
+    /// The start of the heap region must be valid for writes.
+    #[inline(always)]
+    unsafe fn set_position(&self, position: usize) {
+        let state = &mut *(self.start as *mut StackState);
+        state.position = position;
+
+        if position > state.peak {
+            state.peak = position;
+        }
+    }
+}
+
+#[allow(clippy::arithmetic_side_effects)]
+unsafe impl GlobalAlloc for StackAllocator {
+    #[inline]
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let position = self.position();
+        // Align the position to the layout alignment.
+        let ptr = (position + (layout.align() - 1)) & !(layout.align() - 1);
+
+        match ptr.checked_add(layout.size()) {
+            Some(end) if end <= self.start + self.len => {
+                self.set_position(end);
+                ptr as *mut u8
+            }
+            _ => {
+                out_of_memory(layout.size());
+                null_mut()
+            }
+        }
+    }
+
+    #[inline]
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        // Only the most recent allocation can be reclaimed.
+        if ptr as usize + layout.size() == self.position() {
+            self.set_position(ptr as usize);
+        }
+    }
+
+    #[inline]
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        if ptr as usize + layout.size() == self.position() {
+            // The most recent allocation can be resized in place.
+            match (ptr as usize).checked_add(new_size) {
+                Some(end) if end <= self.start + self.len => {
+                    self.set_position(end);
+                    ptr
+                }
+                _ => {
+                    out_of_memory(new_size);
+                    null_mut()
+                }
+            }
+        } else if new_size <= layout.size() {
+            // Shrinking any other allocation keeps the same memory.
+            ptr
+        } else {
+            let new_ptr = self.alloc(Layout::from_size_align_unchecked(new_size, layout.align()));
+
+            if !new_ptr.is_null() {
+                copy_nonoverlapping(ptr, new_ptr, layout.size());
+            }
+
+            new_ptr
+        }
+    }
+}
+
+/// Number of size classes of the [`FreeListAllocator`].
+const SIZE_CLASSES: usize = 8;
+
+/// Size (in bytes) of the smallest size class of the [`FreeListAllocator`].
+const MIN_SIZE_CLASS: usize = 16;
+
+/// Size (in bytes) of the largest size class of the [`FreeListAllocator`].
+const MAX_SIZE_CLASS: usize = MIN_SIZE_CLASS << (SIZE_CLASSES - 1);
+
+/// State of a [`FreeListAllocator`], stored at the start of the heap region.
+#[repr(C)]
+struct FreeListState {
+    /// Current position of the heap.
+    position: usize,
+
+    /// Head of the list of free blocks for each size class.
+    free: [usize; SIZE_CLASSES],
+}
+
+/// An allocator that reuses deallocated memory of small allocations.
+///
+/// Allocations are grouped into size classes of power-of-two sizes, from `16` to
+/// `2048` bytes. Each size class has a list of free blocks: deallocating memory
+/// adds its block to the list, and allocating memory reuses a block from the list
+/// when one is available. New blocks are allocated from the start of the heap
+/// region towards its end.
+///
+/// Allocations larger than the largest size class are not reused, in the same way
+/// as the [`BumpAllocator`].
+///
+/// The allocator is suitable for programs that allocate and deallocate values of
+/// similar sizes repeatedly. Since a block is aligned to its size, there might be
+/// memory lost to padding when allocating blocks of different size classes.
+pub struct FreeListAllocator {
+    pub start: usize,
+    pub len: usize,
+}
+
+impl FreeListAllocator {
+    /// Returns the usage of the heap.
+    ///
+    /// The memory in use includes the blocks in the free lists, since they are only
+    /// available to allocations of the same size class. Therefore, the memory in use
+    /// is always the peak usage.
+    #[inline]
+    pub fn usage(&self) -> HeapUsage {
+        let reserved = self.start + size_of::<FreeListState>();
+        // SAFETY: The start of the heap region holds the allocator state.
+        let position = core::cmp::max(
+            unsafe { (*(self.start as *const FreeListState)).position },
+            reserved,
+        );
+        let used = position - reserved;
+
+        HeapUsage {
+            used,
+            peak: used,
+            remaining: self.start + self.len - position,
+        }
+    }
+
+    /// Returns the allocator state.
+    ///
+    /// # Safety
+    ///
+    /// The start of the heap region must be valid for reads and writes.
+    #[allow(clippy::mut_from_ref)]
+    #[inline(always)]
+    unsafe fn state(&self) -> &mut FreeListState {
+        let state = &mut *(self.start as *mut FreeListState);
+
+        if state.position == 0 {
+            // First time, the position is right after the allocator state.
+            state.position = self.start + size_of::<FreeListState>();
+        }
+
+        state
+    }
+
+    /// Returns the size class for the given layout, if it fits in one.
+    #[inline(always)]
+    fn size_class(layout: &Layout) -> Option<usize> {
+        // The size of a block is also its alignment, so the size class must be
+        // large enough to satisfy both the size and alignment of the layout.
+        let size = core::cmp::max(layout.size(), layout.align());
+
+        if size <= MAX_SIZE_CLASS {
+            let size = core::cmp::max(size, MIN_SIZE_CLASS).next_power_of_two();
+            Some((size.trailing_zeros() - MIN_SIZE_CLASS.trailing_zeros()) as usize)
+        } else {
+            None
+        }
+    }
+
+    /// Allocates memory from the unused part of the heap.
+    ///
+    /// # Safety
+    ///
+    /// The `align` must be a power of two.
+    #[allow(clippy::arithmetic_side_effects)]
+    #[inline(always)]
+    unsafe fn bump(&self, state: &mut FreeListState, size: usize, align: usize) -> *mut u8 {
+        let ptr = (state.position + (align - 1)) & !(align - 1);
+
+        match ptr.checked_add(size) {
+            Some(end) if end <= self.start + self.len => {
+                state.position = end;
+                ptr as *mut u8
+            }
+            _ => {
+                out_of_memory(size);
+                null_mut()
+            }
+        }
+    }
+}
+
+unsafe impl GlobalAlloc for FreeListAllocator {
+    #[inline]
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let state = self.state();
+
+        match Self::size_class(&layout) {
+            Some(class) => {
+                let head = state.free.get_unchecked_mut(class);
+
+                if *head != 0 {
+                    // Reuse a free block; the first bytes of a free block store
+                    // the address of the next free block.
+                    let ptr = *head as *mut u8;
+                    *head = *(ptr as *const usize);
+                    ptr
+                } else {
+                    let size = MIN_SIZE_CLASS << class;
+                    self.bump(state, size, size)
+                }
+            }
+            None => self.bump(state, layout.size(), layout.align()),
+        }
+    }
+
+    #[inline]
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        if let Some(class) = Self::size_class(&layout) {
+            let head = self.state().free.get_unchecked_mut(class);
+            // Blocks are at least `MIN_SIZE_CLASS` bytes and aligned to their
+            // size, so they can always hold the address of the next free block.
+            *(ptr as *mut usize) = *head;
+            *head = ptr as usize;
+        }
+    }
+
+    #[inline]
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_layout = Layout::from_size_align_unchecked(new_size, layout.align());
+
+        match (Self::size_class(&layout), Self::size_class(&new_layout)) {
+            // The block of the allocation is large enough for the new size.
+            (Some(current), Some(new)) if current == new => ptr,
+            _ => {
+                let new_ptr = self.alloc(new_layout);
+
+                if !new_ptr.is_null() {
+                    copy_nonoverlapping(ptr, new_ptr, core::cmp::min(layout.size(), new_size));
+                    self.dealloc(ptr, layout);
+                }
+
+                new_ptr
+            }
+        }
+    }
+}
+
+/// Logs that an allocation failed because the heap is exhausted.
+///
+/// The program will abort right after this, so the message is the only indication
+/// of the cause of the failure. When the program needs more heap, it should request
+/// a larger heap frame and set the heap length on the [`crate::default_allocator!`]
+/// macro accordingly.
+#[cold]
+fn out_of_memory(size: usize) {
+    crate::log::sol_log("** OUT OF HEAP MEMORY **");
+    crate::log::sol_log_64(0, 0, 0, 0, size as u64);
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use std::vec;
+
+    use super::*;
+
+    /// Length of the heap used in the tests.
+    const HEAP: usize = 4096;
+
+    /// Creates a zero-initialized and aligned heap region.
+    fn heap() -> std::vec::Vec<u64> {
+        vec![0u64; HEAP / size_of::<u64>()]
+    }
+
+    #[test]
+    fn test_stack_allocator() {
+        let mut heap = heap();
+        let allocator = StackAllocator {
+            start: heap.as_mut_ptr() as usize,
+            len: HEAP,
+        };
+        let layout = Layout::from_size_align(100, 8).unwrap();
+
+        unsafe {
+            let first = allocator.alloc(layout);
+            assert_eq!(first as usize, allocator.start + size_of::<StackState>());
+
+            // The most recent allocation is reclaimed.
+            let second = allocator.alloc(layout);
+            allocator.dealloc(second, layout);
+            assert_eq!(allocator.alloc(layout), second);
+
+            // Growing the most recent allocation happens in place.
+            let grown = allocator.realloc(second, layout, 200);
+            assert_eq!(grown, second);
+
+            // Growing any other allocation moves it.
+            let moved = allocator.realloc(first, layout, 200);
+            assert_ne!(moved, first);
+
+            // Allocations larger than the remaining heap fail.
+            let large = Layout::from_size_align(HEAP, 8).unwrap();
+            assert!(allocator.alloc(large).is_null());
+        }
+    }
+
+    #[test]
+    fn test_stack_allocator_scope() {
+        let mut heap = heap();
+        let allocator = StackAllocator {
+            start: heap.as_mut_ptr() as usize,
+            len: HEAP,
+        };
+        let layout = Layout::from_size_align(100, 8).unwrap();
+
+        unsafe {
+            let checkpoint = allocator.checkpoint();
+
+            let ptr = allocator.scope(|| {
+                allocator.alloc(layout);
+                allocator.alloc(layout) as usize
+            });
+
+            assert_eq!(allocator.checkpoint(), checkpoint);
+            // Memory used inside the scope is available again.
+            assert_eq!(allocator.alloc(layout) as usize, checkpoint.0);
+            assert!(ptr > checkpoint.0);
+        }
+    }
+
+    #[test]
+    fn test_heap_usage() {
+        let layout = Layout::from_size_align(100, 8).unwrap();
+
+        let mut bump_heap = heap();
+        let allocator = BumpAllocator {
+            start: bump_heap.as_mut_ptr() as usize,
+            len: HEAP,
+        };
+
+        assert_eq!(
+            allocator.usage(),
+            HeapUsage {
+                used: 0,
+                peak: 0,
+                remaining: HEAP - size_of::<usize>(),
+            }
+        );
+
+        unsafe { allocator.alloc(layout) };
+        // The allocation is aligned to `8` bytes from the end of the heap.
+        assert_eq!(allocator.usage().used, 104);
+        assert_eq!(allocator.usage().peak, 104);
+        assert_eq!(allocator.usage().remaining, HEAP - size_of::<usize>() - 104);
+
+        let mut stack_heap = heap();
+        let allocator = StackAllocator {
+            start: stack_heap.as_mut_ptr() as usize,
+            len: HEAP,
+        };
+
+        assert_eq!(allocator.usage().used, 0);
+
+        unsafe {
+            allocator.scope(|| {
+                allocator.alloc(layout);
+                allocator.alloc(layout);
+            });
+            allocator.alloc(layout);
+        }
+
+        assert_eq!(
+            allocator.usage(),
+            HeapUsage {
+                used: 100,
+                // The second allocation is aligned to `8` bytes.
+                peak: 204,
+                remaining: HEAP - size_of::<StackState>() - 100,
+            }
+        );
+
+        let mut free_list_heap = heap();
+        let allocator = FreeListAllocator {
+            start: free_list_heap.as_mut_ptr() as usize,
+            len: HEAP,
+        };
+
+        assert_eq!(allocator.usage().used, 0);
+
+        // The block is aligned to its size class (`128` bytes), so the memory in use
+        // includes the padding after the allocator state.
+        let end = unsafe {
+            let ptr = allocator.alloc(layout);
+            allocator.dealloc(ptr, layout);
+            ptr as usize + 128
+        };
+        let used = end - (allocator.start + size_of::<FreeListState>());
+
+        assert_eq!(allocator.usage().used, used);
+        assert_eq!(allocator.usage().peak, used);
+    }
+
+    #[test]
+    fn test_free_list_allocator() {
+        let mut heap = heap();
+        let allocator = FreeListAllocator {
+            start: heap.as_mut_ptr() as usize,
+            len: HEAP,
+        };
+
+        assert_eq!(
+            FreeListAllocator::size_class(&Layout::from_size_align(1, 1).unwrap()),
+            Some(0)
+        );
+        assert_eq!(
+            FreeListAllocator::size_class(&Layout::from_size_align(17, 8).unwrap()),
+            Some(1)
+        );
+        assert_eq!(
+            FreeListAllocator::size_class(&Layout::from_size_align(8, 64).unwrap()),
+            Some(2)
+        );
+        assert_eq!(
+            FreeListAllocator::size_class(&Layout::from_size_align(2049, 8).unwrap()),
+            None
+        );
+
+        let small = Layout::from_size_align(24, 8).unwrap();
+        let other = Layout::from_size_align(100, 8).unwrap();
+
+        unsafe {
+            let first = allocator.alloc(small);
+            let second = allocator.alloc(small);
+            assert_eq!(first as usize % 32, 0);
+            assert_ne!(first, second);
+
+            // Deallocated blocks are reused in LIFO order.
+            allocator.dealloc(first, small);
+            allocator.dealloc(second, small);
+            assert_eq!(allocator.alloc(small), second);
+            assert_eq!(allocator.alloc(small), first);
+
+            // Blocks of a different size class are not reused.
+            allocator.dealloc(first, small);
+            assert_ne!(allocator.alloc(other), first);
+
+            // Resizing within the same size class keeps the block.
+            assert_eq!(allocator.realloc(second, small, 32), second);
+
+            // Resizing to a different size class moves the block and
+            // frees the previous one.
+            *second = 42;
+            let moved = allocator.realloc(second, small, 64);
+            assert_ne!(moved, second);
+            assert_eq!(*moved, 42);
+            assert_eq!(allocator.alloc(small), second);
+
+            // Allocations larger than the remaining heap fail.
+            let large = Layout::from_size_align(HEAP, 8).unwrap();
+            assert!(allocator.alloc(large).is_null());
+        }
+    }
+}
@@ -0,0 +1,222 @@
+//! Heap, stack and lamports diagnostics.
+//!
+//! The logging helpers only emit messages when the `diagnostics` crate feature is
+//! enabled; otherwise they are no-ops. This allows programs to keep the calls in
+//! place and only enable the logs on specific builds, e.g., when running tests.
+
+#[cfg(target_os = "solana")]
+use super::{STACK_FRAME_SIZE, STACK_START_ADDRESS};
+
+/// Usage of the heap memory region managed by an allocator.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct HeapUsage {
+    /// Number of bytes currently in use.
+    pub used: usize,
+
+    /// Maximum number of bytes in use at any point.
+    pub peak: usize,
+
+    /// Number of bytes still available for allocations.
+    pub remaining: usize,
+}
+
+impl HeapUsage {
+    /// Logs the heap usage.
+    ///
+    /// The message has the format `heap: used=<bytes> peak=<bytes> remaining=<bytes>`.
+    /// Unlike the `log_heap_usage` function defined by the allocator macros, this
+    /// method always logs the message.
+    pub fn log(&self) {
+        log_values(&[
+            ("heap: used=", self.used),
+            (" peak=", self.peak),
+            (" remaining=", self.remaining),
+        ]);
+    }
+}
+
+/// Returns the approximate depth of the call stack, in number of stack frames.
+///
+/// The depth is calculated from the address of a local variable, which lies within
+/// the stack frame of the caller. It assumes fixed-size stack frames of
+/// [`STACK_FRAME_SIZE`](crate::entrypoint::STACK_FRAME_SIZE) bytes, each followed by
+/// a gap of the same size. Since a program can use at most
+/// [`MAX_CALL_DEPTH`](crate::entrypoint::MAX_CALL_DEPTH) frames, this is useful to
+/// find out how close a code path gets to the limit.
+///
+/// When the program is compiled on a target different than `"solana"`, this
+/// function always returns `0`.
+#[inline(always)]
+pub fn stack_depth() -> usize {
+    #[cfg(target_os = "solana")]
+    {
+        let probe = 0u8;
+        let address = core::ptr::addr_of!(probe) as usize;
+
+        (address.saturating_sub(STACK_START_ADDRESS as usize) / (2 * STACK_FRAME_SIZE)) + 1
+    }
+
+    #[cfg(not(target_os = "solana"))]
+    0
+}
+
+/// Logs the approximate depth of the call stack.
+///
+/// The message has the format `stack: depth=<frames>`. See [`stack_depth`] for
+/// details on how the depth is calculated.
+///
+/// This function only logs the message when the `diagnostics` crate feature is
+/// enabled.
+#[inline(always)]
+pub fn log_stack_usage() {
+    #[cfg(feature = "diagnostics")]
+    log_values(&[("stack: depth=", stack_depth())]);
+}
+
+/// Defines a `log_heap_usage` function that logs the usage of the global allocator.
+///
+/// This is used by the allocator macros and only logs the heap usage when the
+/// `diagnostics` crate feature is enabled.
+#[cfg(feature = "diagnostics")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __log_heap_usage {
+    () => {
+        /// Logs the usage of the program heap.
+        ///
+        /// The message has the format `heap: used=<bytes> peak=<bytes> remaining=<bytes>`.
+        #[inline(never)]
+        pub fn log_heap_usage() {
+            #[cfg(target_os = "solana")]
+            A.usage().log();
+        }
+    };
+}
+
+/// Defines a `log_heap_usage` function that logs the usage of the global allocator.
+///
+/// This is used by the allocator macros and only logs the heap usage when the
+/// `diagnostics` crate feature is enabled.
+#[cfg(not(feature = "diagnostics"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __log_heap_usage {
+    () => {
+        /// Logs the usage of the program heap.
+        ///
+        /// This is a no-op since the `diagnostics` feature of `pinocchio` is disabled.
+        #[inline(always)]
+        pub fn log_heap_usage() {}
+    };
+}
+
+/// Runs the instruction, checking that the total lamports of the accounts is the same
+/// before and after it succeeds.
+///
+/// This is used by the `program_entrypoint!` macro and only checks the lamports when
+/// the `diagnostics` crate feature is enabled.
+#[cfg(feature = "diagnostics")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __check_lamports {
+    ( $enabled:expr, $accounts:expr, $instruction:expr ) => {{
+        let lamports = ($enabled).then(|| $crate::account_info::total_lamports($accounts));
+        let result = $instruction;
+
+        if let (Ok(()), Some(lamports)) = (&result, lamports) {
+            assert_eq!(
+                lamports,
+                $crate::account_info::total_lamports($accounts),
+                "total lamports of the instruction accounts changed"
+            );
+        }
+
+        result
+    }};
+}
+
+/// Runs the instruction, checking that the total lamports of the accounts is the same
+/// before and after it succeeds.
+///
+/// This is used by the `program_entrypoint!` macro and only checks the lamports when
+/// the `diagnostics` crate feature is enabled.
+#[cfg(not(feature = "diagnostics"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __check_lamports {
+    ( $enabled:expr, $accounts:expr, $instruction:expr ) => {
+        $instruction
+    };
+}
+
+/// Logs a message composed of pairs of labels and decimal values.
+fn log_values(values: &[(&str, usize)]) {
+    let mut message = [0u8; 96];
+    let mut offset = 0;
+
+    for (label, value) in values {
+        offset += write_str(&mut message[offset..], label);
+        offset += write_decimal(&mut message[offset..], *value);
+    }
+
+    // SAFETY: The message only contains ASCII characters.
+    crate::log::sol_log(unsafe { core::str::from_utf8_unchecked(&message[..offset]) });
+}
+
+/// Writes a `str` to the buffer, returning the number of bytes written.
+///
+/// The `str` is truncated if the buffer is not large enough.
+fn write_str(buffer: &mut [u8], value: &str) -> usize {
+    let length = core::cmp::min(buffer.len(), value.len());
+    buffer[..length].copy_from_slice(&value.as_bytes()[..length]);
+    length
+}
+
+/// Writes the decimal representation of a value to the buffer, returning
+/// the number of bytes written.
+///
+/// The value is truncated if the buffer is not large enough.
+fn write_decimal(buffer: &mut [u8], mut value: usize) -> usize {
+    let mut digits = [b'0'; 20];
+    let mut offset = digits.len();
+
+    loop {
+        offset -= 1;
+        digits[offset] = b'0' + (value % 10) as u8;
+        value /= 10;
+
+        if value == 0 {
+            break;
+        }
+    }
+
+    // SAFETY: The digits are ASCII characters.
+    write_str(buffer, unsafe {
+        core::str::from_utf8_unchecked(&digits[offset..])
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_decimal() {
+        let mut buffer = [0u8; 20];
+
+        assert_eq!(write_decimal(&mut buffer, 0), 1);
+        assert_eq!(&buffer[..1], b"0");
+
+        assert_eq!(write_decimal(&mut buffer, 262144), 6);
+        assert_eq!(&buffer[..6], b"262144");
+
+        assert_eq!(write_decimal(&mut buffer[..3], 262144), 3);
+        assert_eq!(&buffer[..3], b"262");
+    }
+
+    #[test]
+    fn test_stack_depth() {
+        // Off-chain, the stack depth is not available.
+        assert_eq!(stack_depth(), 0);
+    }
+}
@@ -96,6 +96,90 @@ macro_rules! lazy_program_entrypoint {
     };
 }
 
+/// Declare the lazy program entrypoint with access to the instruction data upfront.
+///
+/// This entrypoint is similar to [`crate::lazy_program_entrypoint!`], but the
+/// [`InstructionContext`] provides access to the instruction data and program id before any
+/// account is read. This allows the program to dispatch on the instruction discriminator first
+/// and only read the accounts each instruction needs.
+///
+/// Runtimes implementing [SIMD-0321] pass a pointer to the instruction data as the second
+/// argument of the entrypoint, which is used by the [`InstructionContext`]. On runtimes that
+/// do not pass the pointer, the context locates the instruction data by scanning the accounts
+/// in the input buffer upfront, which consumes compute units proportional to the number of
+/// accounts.
+///
+/// Like [`crate::lazy_program_entrypoint!`], this macro does not set up a global allocator
+/// nor a panic handler.
+///
+/// The only argument is the name of a function with this type signature:
+///
+/// ```ignore
+/// fn process_instruction(
+///    mut context: InstructionContext, // wrapper around the input buffer
+/// ) -> ProgramResult;
+/// ```
+///
+/// # Example
+///
+/// ```no_run
+/// #[cfg(feature = "bpf-entrypoint")]
+/// pub mod entrypoint {
+///
+///     use pinocchio::{
+///         data_first_program_entrypoint,
+///         default_allocator,
+///         default_panic_handler,
+///         entrypoint::InstructionContext,
+///         program_error::ProgramError,
+///         ProgramResult
+///     };
+///
+///     data_first_program_entrypoint!(process_instruction);
+///     default_allocator!();
+///     default_panic_handler!();
+///
+///     pub fn process_instruction(
+///         mut context: InstructionContext,
+///     ) -> ProgramResult {
+///         let discriminator = context
+///             .instruction_data()?
+///             .first()
+///             .copied()
+///             .ok_or(ProgramError::InvalidInstructionData)?;
+///
+///         match discriminator {
+///             0 => {
+///                 let _account = context.next_account()?;
+///                 Ok(())
+///             }
+///             _ => Err(ProgramError::InvalidInstructionData),
+///         }
+///     }
+///
+/// }
+/// ```
+///
+/// [SIMD-0321]: https://github.com/solana-foundation/solana-improvement-documents/pull/321
+#[macro_export]
+macro_rules! data_first_program_entrypoint {
+    ( $process_instruction:expr ) => {
+        /// Program entrypoint.
+        #[no_mangle]
+        pub unsafe extern "C" fn entrypoint(input: *mut u8, instruction_data: *const u8) -> u64 {
+            match $process_instruction(
+                $crate::entrypoint::lazy::InstructionContext::new_with_instruction_data_unchecked(
+                    input,
+                    instruction_data,
+                ),
+            ) {
+                Ok(_) => $crate::SUCCESS,
+                Err(error) => error.into(),
+            }
+        }
+    };
+}
+
 /// Context to access data from the input buffer for the instruction.
 ///
 /// This is a wrapper around the input buffer that provides methods to read the accounts
@@ -110,6 +194,13 @@ pub struct InstructionContext {
     ///
     /// This value is decremented each time [`next_account`] is called.
     remaining: u64,
+
+    /// Pointer to the instruction data, if it is known upfront.
+    ///
+    /// The length of the instruction data is stored in the `8` bytes preceding
+    /// it. This pointer is null when the instruction data can only be located
+    /// after all accounts have been read.
+    instruction_data: *const u8,
 }
 
 impl InstructionContext {
@@ -151,9 +242,41 @@ impl InstructionContext {
             // SAFETY: Read the number of accounts from the input buffer serialized
             // by the SVM loader.
             remaining: unsafe { *(input as *const u64) },
+            instruction_data: core::ptr::null(),
         }
     }
 
+    /// Creates a new [`InstructionContext`] for the input buffer with access to the
+    /// instruction data before any account is read.
+    ///
+    /// The `instruction_data` pointer is the one passed by the runtime as the second
+    /// argument of the entrypoint ([SIMD-0321]). When it is null, the instruction data is
+    /// located by scanning the accounts in the input buffer.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure that the input buffer is valid, i.e., it represents
+    /// the program input parameters serialized by the SVM loader, and that the
+    /// `instruction_data` pointer is either null or points to the instruction data
+    /// of the input buffer.
+    ///
+    /// [SIMD-0321]: https://github.com/solana-foundation/solana-improvement-documents/pull/321
+    #[inline(always)]
+    pub unsafe fn new_with_instruction_data_unchecked(
+        input: *mut u8,
+        instruction_data: *const u8,
+    ) -> Self {
+        let mut context = Self::new_unchecked(input);
+
+        context.instruction_data = if instruction_data.is_null() {
+            context.find_instruction_data()
+        } else {
+            instruction_data
+        };
+
+        context
+    }
+
     /// Reads the next account for the instruction.
     ///
     /// The account is represented as a [`MaybeAccount`], since it can either
@@ -189,6 +312,91 @@ impl InstructionContext {
         self.read_account()
     }
 
+    /// Reads the next account for the instruction, resolving duplicated accounts.
+    ///
+    /// Each account read is recorded in the `cache`, which is used to map a duplicated
+    /// account back to the original [`AccountInfo`]. Since duplicated accounts are
+    /// represented by the index of the original account, all accounts must be read
+    /// using this method with the same `cache`, starting from the first account.
+    ///
+    /// The `cache` only records up to `ACCOUNTS` accounts; accounts after that are
+    /// still returned, but cannot be referenced by duplicated accounts.
+    ///
+    /// # Error
+    ///
+    /// Returns a [`ProgramError::NotEnoughAccountKeys`] error if there are
+    /// no remaining accounts, or a [`ProgramError::InvalidArgument`] error if the
+    /// account is a duplicate of an account not recorded in the `cache`.
+    #[inline(always)]
+    pub fn next_account_resolved<const ACCOUNTS: usize>(
+        &mut self,
+        cache: &mut AccountCache<ACCOUNTS>,
+    ) -> Result<AccountInfo, ProgramError> {
+        let account = match self.next_account()? {
+            MaybeAccount::Account(account) => account,
+            MaybeAccount::Duplicated(index) => cache
+                .get(index as usize)
+                .ok_or(ProgramError::InvalidArgument)?,
+        };
+
+        cache.push(account.raw);
+
+        Ok(account)
+    }
+
+    /// Skips the next `n` accounts for the instruction.
+    ///
+    /// Skipped accounts are not recorded in an [`AccountCache`], so this method should
+    /// not be mixed with [`Self::next_account_resolved`] when later accounts can be
+    /// duplicates of earlier ones.
+    ///
+    /// # Error
+    ///
+    /// Returns a [`ProgramError::NotEnoughAccountKeys`] error if there are
+    /// less than `n` remaining accounts.
+    #[inline(always)]
+    pub fn skip_accounts(&mut self, n: u64) -> Result<(), ProgramError> {
+        self.remaining = self
+            .remaining
+            .checked_sub(n)
+            .ok_or(ProgramError::NotEnoughAccountKeys)?;
+
+        for _ in 0..n {
+            // SAFETY: There are at least `n` remaining accounts.
+            unsafe { self.read_account() };
+        }
+
+        Ok(())
+    }
+
+    /// Returns the next account for the instruction without moving past it.
+    ///
+    /// This can be used to inspect the next account, e.g., its key, before deciding
+    /// whether to read or skip it.
+    ///
+    /// # Error
+    ///
+    /// Returns a [`ProgramError::NotEnoughAccountKeys`] error if there are
+    /// no remaining accounts.
+    #[inline(always)]
+    pub fn peek(&self) -> Result<MaybeAccount, ProgramError> {
+        if self.remaining == 0 {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        }
+
+        let account: *mut Account = self.buffer as *mut Account;
+
+        // SAFETY: There is at least one remaining account at the current position
+        // of the input buffer.
+        Ok(unsafe {
+            if (*account).borrow_state == NON_DUP_MARKER {
+                MaybeAccount::Account(AccountInfo { raw: account })
+            } else {
+                MaybeAccount::Duplicated((*account).borrow_state)
+            }
+        })
+    }
+
     /// Returns the number of remaining accounts.
     ///
     /// This value is decremented each time [`Self::next_account`] is called.
@@ -199,11 +407,12 @@ impl InstructionContext {
 
     /// Returns the data for the instruction.
     ///
-    /// This method can only be used after all accounts have been read; otherwise, it will
+    /// Unless the context was created with access to the instruction data upfront,
+    /// this method can only be used after all accounts have been read; otherwise, it will
     /// return a [`ProgramError::InvalidInstructionData`] error.
     #[inline(always)]
     pub fn instruction_data(&self) -> Result<&[u8], ProgramError> {
-        if self.remaining > 0 {
+        if self.remaining > 0 && self.instruction_data.is_null() {
             return Err(ProgramError::InvalidInstructionData);
         }
 
@@ -214,23 +423,24 @@ impl InstructionContext {
     ///
     /// # Safety
     ///
-    /// It is up to the caller to guarantee that all accounts have been read; calling this method
+    /// Unless the context was created with access to the instruction data upfront, it is up
+    /// to the caller to guarantee that all accounts have been read; calling this method
     /// before reading all accounts will result in undefined behavior.
     #[inline(always)]
     pub unsafe fn instruction_data_unchecked(&self) -> &[u8] {
-        let data_len = *(self.buffer as *const usize);
-        // shadowing the input to avoid leaving it in an inconsistent position
-        let data = self.buffer.add(core::mem::size_of::<u64>());
+        let data = self.instruction_data_ptr();
+        let data_len = *(data.sub(core::mem::size_of::<u64>()) as *const usize);
         core::slice::from_raw_parts(data, data_len)
     }
 
     /// Returns the program id for the instruction.
     ///
-    /// This method can only be used after all accounts have been read; otherwise, it will
+    /// Unless the context was created with access to the instruction data upfront,
+    /// this method can only be used after all accounts have been read; otherwise, it will
     /// return a [`ProgramError::InvalidInstructionData`] error.
     #[inline(always)]
     pub fn program_id(&self) -> Result<&Pubkey, ProgramError> {
-        if self.remaining > 0 {
+        if self.remaining > 0 && self.instruction_data.is_null() {
             return Err(ProgramError::InvalidInstructionData);
         }
 
@@ -241,12 +451,45 @@ impl InstructionContext {
     ///
     /// # Safety
     ///
-    /// It is up to the caller to guarantee that all accounts have been read; calling this method
+    /// Unless the context was created with access to the instruction data upfront, it is up
+    /// to the caller to guarantee that all accounts have been read; calling this method
     /// before reading all accounts will result in undefined behavior.
     #[inline(always)]
     pub unsafe fn program_id_unchecked(&self) -> &Pubkey {
-        let data_len = *(self.buffer as *const usize);
-        &*(self.buffer.add(core::mem::size_of::<u64>() + data_len) as *const Pubkey)
+        let data = self.instruction_data_ptr();
+        let data_len = *(data.sub(core::mem::size_of::<u64>()) as *const usize);
+        &*(data.add(data_len) as *const Pubkey)
+    }
+
+    /// Returns a pointer to the instruction data.
+    ///
+    /// When the instruction data is not known upfront, the pointer is derived from the
+    /// current position of the input buffer, which is only valid after all accounts
+    /// have been read.
+    #[inline(always)]
+    unsafe fn instruction_data_ptr(&self) -> *const u8 {
+        if self.instruction_data.is_null() {
+            self.buffer.add(core::mem::size_of::<u64>())
+        } else {
+            self.instruction_data
+        }
+    }
+
+    /// Locates the instruction data by reading the remaining accounts, without moving
+    /// the position of the input buffer.
+    #[inline(always)]
+    unsafe fn find_instruction_data(&self) -> *const u8 {
+        let mut scan = Self {
+            buffer: self.buffer,
+            remaining: self.remaining,
+            instruction_data: core::ptr::null(),
+        };
+
+        for _ in 0..scan.remaining {
+            scan.read_account();
+        }
+
+        scan.buffer.add(core::mem::size_of::<u64>())
     }
 
     /// Read an account from the input buffer.
@@ -275,6 +518,73 @@ impl InstructionContext {
     }
 }
 
+/// Fixed-capacity cache of the accounts read from an [`InstructionContext`].
+///
+/// The cache is used by [`InstructionContext::next_account_resolved`] to map duplicated
+/// accounts back to the original [`AccountInfo`]. It holds up to `ACCOUNTS` accounts,
+/// so its capacity should match the number of accounts expected by the instruction.
+pub struct AccountCache<const ACCOUNTS: usize> {
+    /// Accounts recorded in the cache.
+    accounts: [*mut Account; ACCOUNTS],
+
+    /// Number of accounts read.
+    len: usize,
+}
+
+impl<const ACCOUNTS: usize> AccountCache<ACCOUNTS> {
+    /// Creates a new empty cache.
+    #[inline(always)]
+    pub const fn new() -> Self {
+        Self {
+            accounts: [core::ptr::null_mut(); ACCOUNTS],
+            len: 0,
+        }
+    }
+
+    /// Returns the number of accounts read.
+    ///
+    /// This can be greater than `ACCOUNTS`, in which case only the first `ACCOUNTS`
+    /// accounts are recorded in the cache.
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if no accounts have been read.
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the account at the given index, if it is recorded in the cache.
+    #[inline(always)]
+    pub fn get(&self, index: usize) -> Option<AccountInfo> {
+        if index < core::cmp::min(self.len, ACCOUNTS) {
+            Some(AccountInfo {
+                raw: self.accounts[index],
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Records an account read from the input buffer.
+    #[inline(always)]
+    fn push(&mut self, account: *mut Account) {
+        if self.len < ACCOUNTS {
+            self.accounts[self.len] = account;
+        }
+
+        self.len += 1;
+    }
+}
+
+impl<const ACCOUNTS: usize> Default for AccountCache<ACCOUNTS> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 /// Wrapper type around an [`AccountInfo`] that may be a duplicate.
 pub enum MaybeAccount {
     /// An [`AccountInfo`] that is not a duplicate.
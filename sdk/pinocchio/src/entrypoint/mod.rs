@@ -1,17 +1,19 @@
 //! Macros and functions for defining the program entrypoint and setting up
 //! global handlers.
 
+mod alloc;
+mod diagnostics;
 pub mod lazy;
 
-pub use lazy::{InstructionContext, MaybeAccount};
+pub use lazy::{AccountCache, InstructionContext, MaybeAccount};
 
-#[cfg(target_os = "solana")]
-pub use alloc::BumpAllocator;
+pub use alloc::{BumpAllocator, FreeListAllocator, HeapCheckpoint, StackAllocator};
 use core::{
     cmp::min,
     mem::{size_of, MaybeUninit},
     slice::from_raw_parts,
 };
+pub use diagnostics::{log_stack_usage, stack_depth, HeapUsage};
 
 use crate::{
     account_info::{Account, AccountInfo, MAX_PERMITTED_DATA_INCREASE},
@@ -25,6 +27,23 @@ pub const HEAP_START_ADDRESS: u64 = 0x300000000;
 /// Length of the heap memory region used for program heap.
 pub const HEAP_LENGTH: usize = 32 * 1024;
 
+/// Maximum length of the heap memory region that can be requested for a
+/// program heap.
+///
+/// Programs can request a heap larger than [`HEAP_LENGTH`] through the
+/// `RequestHeapFrame` instruction of the ComputeBudget program. The requested
+/// length must be a multiple of `1024` bytes and it is limited to `256` KiB.
+pub const MAX_HEAP_LENGTH: usize = 256 * 1024;
+
+/// Start address of the memory region used for program stack.
+pub const STACK_START_ADDRESS: u64 = 0x200000000;
+
+/// Size of a stack frame of a program.
+pub const STACK_FRAME_SIZE: usize = 4 * 1024;
+
+/// Maximum number of nested stack frames of a program.
+pub const MAX_CALL_DEPTH: usize = 64;
+
 #[deprecated(
     since = "0.6.0",
     note = "Use `ProgramResult` from the crate root instead"
@@ -71,6 +90,10 @@ const STATIC_ACCOUNT_DATA: usize = size_of::<Account>() + MAX_PERMITTED_DATA_INC
 /// for the entrypoint, as the default is set to [`crate::MAX_TX_ACCOUNTS`]. If the program
 /// receives more accounts than the specified maximum, these accounts will be ignored.
 ///
+/// A third optional argument specifies the length of the heap used by the global allocator,
+/// which is forwarded to the [`crate::default_allocator!`] macro. See its documentation for
+/// details on how to use a heap larger than the default [`crate::entrypoint::HEAP_LENGTH`].
+///
 /// [global allocator]: https://doc.rust-lang.org/stable/alloc/alloc/trait.GlobalAlloc.html
 /// [maximum number of accounts]: https://github.com/anza-xyz/agave/blob/ccabfcf84921977202fd06d3197cbcea83742133/runtime/src/bank.rs#L3207-L3219
 /// [panic handler]: https://doc.rust-lang.org/stable/core/panic/trait.PanicHandler.html
@@ -124,8 +147,15 @@ macro_rules! entrypoint {
         $crate::entrypoint!($process_instruction, { $crate::MAX_TX_ACCOUNTS });
     };
     ( $process_instruction:expr, $maximum:expr ) => {
+        $crate::entrypoint!(
+            $process_instruction,
+            $maximum,
+            $crate::entrypoint::HEAP_LENGTH
+        );
+    };
+    ( $process_instruction:expr, $maximum:expr, $heap_length:expr ) => {
         $crate::program_entrypoint!($process_instruction, $maximum);
-        $crate::default_allocator!();
+        $crate::default_allocator!($heap_length);
         $crate::default_panic_handler!();
     };
 }
@@ -152,6 +182,10 @@ macro_rules! entrypoint {
 /// expected by instructions of the program. This is useful to reduce the stack size requirement
 /// for the entrypoint, as the default is set to [`crate::MAX_TX_ACCOUNTS`]. If the program
 /// receives more accounts than the specified maximum, these accounts will be ignored.
+///
+/// When the `diagnostics` crate feature is enabled, the entrypoint also checks that the total
+/// lamports of the instruction accounts is the same before and after a successful instruction,
+/// panicking otherwise. The check is skipped when accounts are ignored due to the maximum.
 #[macro_export]
 macro_rules! program_entrypoint {
     ( $process_instruction:expr ) => {
@@ -169,12 +203,17 @@ macro_rules! program_entrypoint {
             let (program_id, count, instruction_data) =
                 $crate::entrypoint::deserialize::<$maximum>(input, &mut accounts);
 
-            // Call the program's entrypoint passing `count` account infos; we know that
-            // they are initialized so we cast the pointer to a slice of `[AccountInfo]`.
-            match $process_instruction(
-                &program_id,
-                core::slice::from_raw_parts(accounts.as_ptr() as _, count),
-                &instruction_data,
+            // We know that the first `count` account infos are initialized so we cast the
+            // pointer to a slice of `[AccountInfo]`.
+            let accounts: &[$crate::account_info::AccountInfo] =
+                core::slice::from_raw_parts(accounts.as_ptr() as _, count);
+
+            // Call the program's entrypoint passing `count` account infos. The lamports
+            // check is only possible when all accounts were deserialized.
+            match $crate::__check_lamports!(
+                count as u64 == *(input as *const u64),
+                accounts,
+                $process_instruction(&program_id, accounts, &instruction_data)
             ) {
                 Ok(()) => $crate::SUCCESS,
                 Err(error) => error.into(),
@@ -517,14 +556,107 @@ macro_rules! nostd_panic_handler {
 /// Default global allocator.
 ///
 /// This macro sets up a default global allocator that uses a bump allocator to allocate memory.
+///
+/// There is an optional argument that allows to specify the length of the heap. By default, the
+/// heap length is set to [`crate::entrypoint::HEAP_LENGTH`] (`32` KiB), which is the heap size that the runtime
+/// makes available to every program. A larger heap must be requested by the transaction using
+/// the `RequestHeapFrame` instruction of the ComputeBudget program; the heap length specified
+/// here should match the requested heap frame. It must be a multiple of `1024` bytes and cannot
+/// be greater than [`crate::entrypoint::MAX_HEAP_LENGTH`], otherwise the macro fails to compile.
+///
+/// Note that the allocator cannot detect the size of the heap frame granted by the runtime.
+/// Using a heap length larger than the requested heap frame results in an access violation
+/// once an allocation crosses the end of the granted heap.
+///
+/// The macro also defines a `log_heap_usage` function that logs the usage of the heap. The
+/// function is a no-op unless the `diagnostics` crate feature is enabled.
+///
+/// # Examples
+///
+/// Setting up a global allocator with a `256` KiB heap:
+///
+/// ```ignore
+/// use pinocchio::{default_allocator, default_panic_handler, program_entrypoint};
+///
+/// program_entrypoint!(process_instruction);
+/// default_allocator!(256 * 1024);
+/// default_panic_handler!();
+/// ```
 #[macro_export]
 macro_rules! default_allocator {
     () => {
+        $crate::default_allocator!($crate::entrypoint::HEAP_LENGTH);
+    };
+    ( $heap_length:expr ) => {
+        const _: () = $crate::entrypoint::check_heap_length($heap_length);
+
         #[cfg(target_os = "solana")]
         #[global_allocator]
         static A: $crate::entrypoint::BumpAllocator = $crate::entrypoint::BumpAllocator {
             start: $crate::entrypoint::HEAP_START_ADDRESS as usize,
-            len: $crate::entrypoint::HEAP_LENGTH,
+            len: $heap_length,
+        };
+
+        /// A default allocator for when the program is compiled on a target different than
+        /// `"solana"`.
+        ///
+        /// This links the `std` library, which will set up a default global allocator.
+        #[cfg(not(target_os = "solana"))]
+        mod __private_alloc {
+            extern crate std as __std;
+        }
+
+        $crate::__log_heap_usage!();
+    };
+}
+
+/// Custom global allocator.
+///
+/// This macro sets up a global allocator of the specified kind. The available kinds are:
+///
+/// * `bump`: a bump allocator that never frees memory ([`crate::entrypoint::BumpAllocator`]).
+///   This is the same allocator set up by [`crate::default_allocator!`].
+/// * `stack`: a bump allocator that reclaims the most recent allocation on deallocation and
+///   resizes it in place on reallocation ([`crate::entrypoint::StackAllocator`]).
+/// * `free_list`: an allocator that reuses deallocated memory of small allocations grouped
+///   into size classes ([`crate::entrypoint::FreeListAllocator`]).
+///
+/// There is a second optional argument that allows to specify the length of the heap, with the
+/// same semantics as the [`crate::default_allocator!`] macro.
+///
+/// Like [`crate::default_allocator!`], the macro defines a `log_heap_usage` function that
+/// logs the usage of the heap when the `diagnostics` crate feature is enabled.
+///
+/// When using the `stack` kind, the macro also defines a `heap_scope` function that runs a
+/// closure and releases all heap memory allocated while running it. The caller must ensure
+/// that no value allocated while running the closure outlives the call.
+///
+/// # Examples
+///
+/// Setting up a `stack` allocator and reusing heap memory across loop iterations:
+///
+/// ```ignore
+/// use pinocchio::{custom_allocator, default_panic_handler, program_entrypoint};
+///
+/// program_entrypoint!(process_instruction);
+/// custom_allocator!(stack);
+/// default_panic_handler!();
+///
+/// for order in orders {
+///     // SAFETY: The values allocated in the closure do not escape it.
+///     unsafe { heap_scope(|| process_order(order)) }?;
+/// }
+/// ```
+#[macro_export]
+macro_rules! custom_allocator {
+    ( @global $allocator:ident, $heap_length:expr ) => {
+        const _: () = $crate::entrypoint::check_heap_length($heap_length);
+
+        #[cfg(target_os = "solana")]
+        #[global_allocator]
+        static A: $crate::entrypoint::$allocator = $crate::entrypoint::$allocator {
+            start: $crate::entrypoint::HEAP_START_ADDRESS as usize,
+            len: $heap_length,
         };
 
         /// A default allocator for when the program is compiled on a target different than
@@ -535,6 +667,44 @@ macro_rules! default_allocator {
         mod __private_alloc {
             extern crate std as __std;
         }
+
+        $crate::__log_heap_usage!();
+    };
+    ( $kind:ident ) => {
+        $crate::custom_allocator!($kind, $crate::entrypoint::HEAP_LENGTH);
+    };
+    ( bump, $heap_length:expr ) => {
+        $crate::custom_allocator!(@global BumpAllocator, $heap_length);
+    };
+    ( stack, $heap_length:expr ) => {
+        $crate::custom_allocator!(@global StackAllocator, $heap_length);
+
+        /// Runs the closure `f` and releases all heap memory allocated while running it.
+        ///
+        /// # Safety
+        ///
+        /// The caller must ensure that no value allocated while running `f` outlives
+        /// the call, including through the value returned by `f`.
+        #[inline(always)]
+        pub unsafe fn heap_scope<R>(f: impl FnOnce() -> R) -> R {
+            #[cfg(target_os = "solana")]
+            {
+                A.scope(f)
+            }
+
+            #[cfg(not(target_os = "solana"))]
+            f()
+        }
+    };
+    ( free_list, $heap_length:expr ) => {
+        $crate::custom_allocator!(@global FreeListAllocator, $heap_length);
+    };
+    ( $kind:ident, $heap_length:expr ) => {
+        compile_error!(concat!(
+            "unknown allocator kind `",
+            stringify!($kind),
+            "`; expected one of `bump`, `stack` or `free_list`"
+        ));
     };
 }
 
@@ -619,46 +789,28 @@ macro_rules! no_allocator {
     };
 }
 
-#[cfg(target_os = "solana")]
-mod alloc {
-    //! The bump allocator used as the default rust heap when running programs.
-
-    extern crate alloc;
-
-    /// The bump allocator used as the default rust heap when running programs.
-    pub struct BumpAllocator {
-        pub start: usize,
-        pub len: usize,
-    }
-
-    /// Integer arithmetic in this global allocator implementation is safe when
-    /// operating on the prescribed [`HEAP_START_ADDRESS`] and [`HEAP_LENGTH`]. Any
-    /// other use may overflow and is thus unsupported and at one's own risk.
-    #[allow(clippy::arithmetic_side_effects)]
-    unsafe impl alloc::alloc::GlobalAlloc for BumpAllocator {
-        /// Allocates memory as a bump allocator.
-        #[inline]
-        unsafe fn alloc(&self, layout: core::alloc::Layout) -> *mut u8 {
-            let pos_ptr = self.start as *mut usize;
-
-            let mut pos = *pos_ptr;
-            if pos == 0 {
-                // First time, set starting position.
-                pos = self.start + self.len;
-            }
-            pos = pos.saturating_sub(layout.size());
-            pos &= !(layout.align().wrapping_sub(1));
-            if pos < self.start + core::mem::size_of::<*mut u8>() {
-                return core::ptr::null_mut();
-            }
-            *pos_ptr = pos;
-            pos as *mut u8
-        }
-        #[inline]
-        unsafe fn dealloc(&self, _: *mut u8, _: core::alloc::Layout) {
-            // I'm a bump allocator, I don't free.
-        }
-    }
+/// Validates the length of the heap used by a global allocator.
+///
+/// The heap length must be a multiple of `1024` bytes and between [`HEAP_LENGTH`] and
+/// [`MAX_HEAP_LENGTH`], matching the heap frame sizes that the runtime accepts.
+///
+/// This function is intended to be used in a `const` context, where an invalid heap
+/// length results in a compile error.
+#[doc(hidden)]
+#[inline(always)]
+pub const fn check_heap_length(heap_length: usize) {
+    assert!(
+        heap_length >= HEAP_LENGTH,
+        "heap length must be at least HEAP_LENGTH (32 KiB)"
+    );
+    assert!(
+        heap_length <= MAX_HEAP_LENGTH,
+        "heap length must not exceed MAX_HEAP_LENGTH (256 KiB)"
+    );
+    assert!(
+        heap_length % 1024 == 0,
+        "heap length must be a multiple of 1024 bytes"
+    );
 }
 
 #[cfg(not(feature = "std"))]
@@ -689,6 +841,7 @@ mod tests {
     };
 
     use super::*;
+    use crate::program_error::ProgramError;
 
     /// The mock program ID used for testing.
     const MOCK_PROGRAM_ID: Pubkey = [5u8; 32];
@@ -988,4 +1141,157 @@ mod tests {
         assert_eq!(&ix_data, parsed_ix_data);
         assert_duplicated_accounts(&accounts, 32);
     }
+
+    #[test]
+    fn test_lazy_next_account_resolved() {
+        let ix_data = [3u8; 100];
+
+        // Input with 5 (3 + 2 duplicated) accounts. The duplicated accounts are
+        // resolved to the account at index 2.
+
+        let mut input = unsafe { create_input_with_duplicates(5, &ix_data, 2) };
+        let mut context = unsafe { InstructionContext::new_unchecked(input.as_mut_ptr()) };
+        let mut cache = AccountCache::<5>::new();
+
+        let accounts = [(); 5].map(|_| context.next_account_resolved(&mut cache).unwrap());
+
+        assert_eq!(cache.len(), 5);
+        assert_eq!(context.remaining(), 0);
+        assert_eq!(context.instruction_data(), Ok(ix_data.as_slice()));
+
+        for (i, account) in accounts[..3].iter().enumerate() {
+            assert_eq!(account.data_len(), i);
+        }
+
+        for account in accounts[3..].iter() {
+            assert_eq!(account.raw, accounts[2].raw);
+        }
+
+        assert_eq!(
+            context.next_account_resolved(&mut cache).err(),
+            Some(ProgramError::NotEnoughAccountKeys)
+        );
+
+        // The cache only has space for the first 2 accounts, so the duplicates of
+        // the account at index 2 cannot be resolved.
+
+        let mut input = unsafe { create_input_with_duplicates(5, &ix_data, 2) };
+        let mut context = unsafe { InstructionContext::new_unchecked(input.as_mut_ptr()) };
+        let mut cache = AccountCache::<2>::new();
+
+        for _ in 0..3 {
+            assert!(context.next_account_resolved(&mut cache).is_ok());
+        }
+
+        assert_eq!(cache.len(), 3);
+        assert!(cache.get(2).is_none());
+        assert_eq!(
+            context.next_account_resolved(&mut cache).err(),
+            Some(ProgramError::InvalidArgument)
+        );
+    }
+
+    #[test]
+    fn test_lazy_skip_and_peek() {
+        let ix_data = [3u8; 100];
+
+        let mut input = unsafe { create_input_with_duplicates(5, &ix_data, 2) };
+        let mut context = unsafe { InstructionContext::new_unchecked(input.as_mut_ptr()) };
+
+        assert!(context.skip_accounts(2).is_ok());
+        assert_eq!(context.remaining(), 3);
+
+        // Peeking does not move past the account.
+
+        let MaybeAccount::Account(peeked) = context.peek().unwrap() else {
+            panic!("expected a non-duplicated account");
+        };
+        assert_eq!(peeked.data_len(), 2);
+        assert_eq!(context.remaining(), 3);
+
+        let account = context.next_account().unwrap().assume_account();
+        assert_eq!(account.raw, peeked.raw);
+
+        assert!(matches!(context.peek(), Ok(MaybeAccount::Duplicated(2))));
+
+        assert_eq!(
+            context.skip_accounts(3),
+            Err(ProgramError::NotEnoughAccountKeys)
+        );
+        assert!(context.skip_accounts(2).is_ok());
+
+        assert_eq!(
+            context.peek().err(),
+            Some(ProgramError::NotEnoughAccountKeys)
+        );
+        assert_eq!(context.instruction_data(), Ok(ix_data.as_slice()));
+        assert_eq!(context.program_id(), Ok(&MOCK_PROGRAM_ID));
+    }
+
+    #[test]
+    fn test_lazy_instruction_data_first() {
+        let ix_data = [3u8; 100];
+
+        // Without the instruction data pointer, the context scans the accounts to
+        // locate the instruction data.
+
+        let mut input = unsafe { create_input_with_duplicates(5, &ix_data, 2) };
+        let mut context = unsafe {
+            InstructionContext::new_with_instruction_data_unchecked(
+                input.as_mut_ptr(),
+                core::ptr::null(),
+            )
+        };
+
+        assert_eq!(context.remaining(), 5);
+        assert_eq!(context.instruction_data(), Ok(ix_data.as_slice()));
+        assert_eq!(context.program_id(), Ok(&MOCK_PROGRAM_ID));
+
+        let instruction_data = context.instruction_data().unwrap().as_ptr();
+
+        // Reading accounts does not affect the instruction data.
+
+        let account = context.next_account().unwrap().assume_account();
+        assert_eq!(account.data_len(), 0);
+        assert_eq!(context.instruction_data(), Ok(ix_data.as_slice()));
+
+        // With the instruction data pointer.
+
+        let mut context = unsafe {
+            InstructionContext::new_with_instruction_data_unchecked(
+                input.as_mut_ptr(),
+                instruction_data,
+            )
+        };
+
+        assert_eq!(context.instruction_data(), Ok(ix_data.as_slice()));
+        assert_eq!(context.program_id(), Ok(&MOCK_PROGRAM_ID));
+
+        assert!(context.skip_accounts(5).is_ok());
+        assert_eq!(context.instruction_data(), Ok(ix_data.as_slice()));
+
+        // Without accounts.
+
+        let mut input = unsafe { create_input(0, &ix_data) };
+        let context = unsafe {
+            InstructionContext::new_with_instruction_data_unchecked(
+                input.as_mut_ptr(),
+                core::ptr::null(),
+            )
+        };
+
+        assert_eq!(context.instruction_data(), Ok(ix_data.as_slice()));
+        assert_eq!(context.program_id(), Ok(&MOCK_PROGRAM_ID));
+    }
+
+    #[test]
+    fn test_check_heap_length() {
+        check_heap_length(HEAP_LENGTH);
+        check_heap_length(64 * 1024);
+        check_heap_length(MAX_HEAP_LENGTH);
+
+        assert!(std::panic::catch_unwind(|| check_heap_length(HEAP_LENGTH - 1024)).is_err());
+        assert!(std::panic::catch_unwind(|| check_heap_length(MAX_HEAP_LENGTH + 1024)).is_err());
+        assert!(std::panic::catch_unwind(|| check_heap_length(HEAP_LENGTH + 1)).is_err());
+    }
 }
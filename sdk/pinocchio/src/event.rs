@@ -0,0 +1,610 @@
+//! Structured events emitted through `sol_log_data`.
+//!
+//! An event is serialized as an 8-byte discriminator followed by its fields in a
+//! fixed little-endian layout, and logged as a single `base64` encoded slice. The
+//! discriminator is the first 8 bytes of `sha256("event:<Name>")` and fixed-size
+//! fields use the same encoding as Borsh, making events compatible with Anchor's
+//! event parsers.
+//!
+//! Events are usually declared with the [`event!`](crate::event!) macro:
+//!
+//! ```
+//! use pinocchio::{event, event::Event, pubkey::Pubkey};
+//!
+//! event! {
+//!     /// Emitted when tokens are transferred.
+//!     pub struct Transfer {
+//!         pub from: Pubkey,
+//!         pub amount: u64,
+//!     }
+//! }
+//!
+//! Transfer { from: [1; 32], amount: 500 }.emit();
+//! ```
+//!
+//! Unlike Anchor's `#[event]` attribute, `event!` is a declarative macro wrapping
+//! the struct declaration, since `pinocchio` does not depend on a procedural macro
+//! crate. The [`Event`] trait can also be implemented manually, e.g., for types
+//! declared elsewhere.
+//!
+//! # Self-CPI events
+//!
+//! Logs can be truncated when a transaction produces too many of them, in which case
+//! events emitted with [`Event::emit`] are lost. As an opt-in alternative,
+//! [`Event::emit_cpi`] emits the event as the instruction data of a cross-program
+//! invocation of the program itself, which is recorded in the transaction inner
+//! instructions. This follows the same scheme as Anchor's `emit_cpi!`:
+//!
+//! * the instruction data is [`EVENT_IX_TAG_LE`] followed by the serialized event;
+//! * the only account of the instruction is the event authority, a program derived
+//!   address with the seed [`EVENT_AUTHORITY_SEED`], which signs the invocation.
+//!
+//! The program must handle the self-invocation in its entrypoint using
+//! [`process_event_instruction`], and the event authority and the program account
+//! must be included in the accounts of the instructions that emit events.
+
+use core::mem::MaybeUninit;
+
+use crate::{
+    account_info::AccountInfo,
+    cpi::invoke_signed,
+    instruction::{AccountMeta, Instruction, Seed, Signer},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sha256::sha256,
+    ProgramResult,
+};
+
+/// Length of an event discriminator.
+pub const DISCRIMINATOR_LEN: usize = 8;
+
+/// Tag identifying the instruction data of a self-CPI event.
+///
+/// This is the same value used by Anchor's `emit_cpi!`.
+pub const EVENT_IX_TAG: u64 = 0x1d9acb512ea545e4;
+
+/// Little-endian bytes of [`EVENT_IX_TAG`], which prefix the instruction data of a
+/// self-CPI event.
+pub const EVENT_IX_TAG_LE: [u8; 8] = EVENT_IX_TAG.to_le_bytes();
+
+/// Seed of the event authority program derived address.
+pub const EVENT_AUTHORITY_SEED: &[u8] = b"__event_authority";
+
+/// Types that can be used as event fields.
+///
+/// Fields are encoded with a fixed number of bytes in little-endian order.
+pub trait EventField: Sized {
+    /// Number of bytes of the encoded field.
+    const LEN: usize;
+
+    /// Writes the field to the buffer, which has exactly [`Self::LEN`] bytes.
+    fn write(&self, buffer: &mut [u8]);
+
+    /// Reads the field from the buffer, which has exactly [`Self::LEN`] bytes.
+    ///
+    /// Returns `None` if the bytes do not represent a valid value.
+    fn read(buffer: &[u8]) -> Option<Self>;
+}
+
+/// Implement the event field trait for integer types.
+macro_rules! impl_event_field_for_integer {
+    ( $($type:ty),* ) => {
+        $(
+            impl EventField for $type {
+                const LEN: usize = core::mem::size_of::<$type>();
+
+                #[inline(always)]
+                fn write(&self, buffer: &mut [u8]) {
+                    buffer.copy_from_slice(&self.to_le_bytes());
+                }
+
+                #[inline(always)]
+                fn read(buffer: &[u8]) -> Option<Self> {
+                    buffer.try_into().ok().map(<$type>::from_le_bytes)
+                }
+            }
+        )*
+    };
+}
+
+impl_event_field_for_integer!(u8, u16, u32, u64, u128, i8, i16, i32, i64, i128);
+
+impl EventField for bool {
+    const LEN: usize = 1;
+
+    #[inline(always)]
+    fn write(&self, buffer: &mut [u8]) {
+        buffer[0] = *self as u8;
+    }
+
+    #[inline(always)]
+    fn read(buffer: &[u8]) -> Option<Self> {
+        match buffer {
+            [0] => Some(false),
+            [1] => Some(true),
+            _ => None,
+        }
+    }
+}
+
+impl<T: EventField, const N: usize> EventField for [T; N] {
+    const LEN: usize = T::LEN * N;
+
+    #[inline]
+    fn write(&self, buffer: &mut [u8]) {
+        for (value, chunk) in self.iter().zip(buffer.chunks_exact_mut(T::LEN.max(1))) {
+            value.write(chunk);
+        }
+    }
+
+    #[inline]
+    fn read(buffer: &[u8]) -> Option<Self> {
+        if buffer.len() != Self::LEN {
+            return None;
+        }
+
+        let mut array = [const { MaybeUninit::<T>::uninit() }; N];
+
+        for (i, value) in array.iter_mut().enumerate() {
+            value.write(T::read(&buffer[i * T::LEN..(i + 1) * T::LEN])?);
+        }
+
+        // SAFETY: All elements of the array have been initialized.
+        Some(unsafe { (array.as_ptr() as *const [T; N]).read() })
+    }
+}
+
+/// Trait for structured events.
+///
+/// This trait is usually implemented with the [`event!`](crate::event!) macro.
+pub trait Event: Sized {
+    /// Discriminator of the event.
+    ///
+    /// Use [`discriminator`] to compute the discriminator compatible with Anchor.
+    const DISCRIMINATOR: [u8; DISCRIMINATOR_LEN];
+
+    /// Number of bytes of the event data, excluding the discriminator.
+    const DATA_LEN: usize;
+
+    /// Writes the event data to the buffer, which has exactly [`Self::DATA_LEN`] bytes.
+    fn write_data(&self, buffer: &mut [u8]);
+
+    /// Reads the event data from the buffer, which has exactly [`Self::DATA_LEN`] bytes.
+    ///
+    /// Returns `None` if the bytes do not represent a valid event.
+    fn read_data(data: &[u8]) -> Option<Self>;
+
+    /// Emits the event.
+    ///
+    /// Implementations should use a stack buffer of `DISCRIMINATOR_LEN + DATA_LEN`
+    /// bytes and call [`emit_event`].
+    fn emit(&self);
+
+    /// Emits the event through a cross-program invocation of the program itself.
+    ///
+    /// The `signer_seeds` are the seeds of the event authority, including its bump
+    /// seed. Implementations should use a stack buffer of
+    /// `EVENT_IX_TAG_LE.len() + DISCRIMINATOR_LEN + DATA_LEN` bytes and call
+    /// [`emit_cpi_event`].
+    fn emit_cpi(
+        &self,
+        program_id: &Pubkey,
+        event_authority: &AccountInfo,
+        signer_seeds: &[Seed],
+    ) -> ProgramResult;
+
+    /// Decodes an event from its serialized bytes, including the discriminator.
+    ///
+    /// Returns `None` if the discriminator or length do not match the event.
+    #[inline]
+    fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() != DISCRIMINATOR_LEN + Self::DATA_LEN {
+            return None;
+        }
+
+        let (discriminator, data) = bytes.split_at(DISCRIMINATOR_LEN);
+
+        if discriminator != Self::DISCRIMINATOR {
+            return None;
+        }
+
+        Self::read_data(data)
+    }
+}
+
+/// Serializes an event into the buffer and logs it with `sol_log_data`.
+///
+/// # Panics
+///
+/// Panics if the buffer is smaller than `DISCRIMINATOR_LEN + E::DATA_LEN` bytes.
+#[inline]
+pub fn emit_event<E: Event>(event: &E, buffer: &mut [u8]) {
+    let buffer = &mut buffer[..DISCRIMINATOR_LEN + E::DATA_LEN];
+    let (discriminator, data) = buffer.split_at_mut(DISCRIMINATOR_LEN);
+
+    discriminator.copy_from_slice(&E::DISCRIMINATOR);
+    event.write_data(data);
+
+    crate::log::sol_log_data(&[buffer]);
+}
+
+/// Serializes an event into the buffer and emits it through a cross-program
+/// invocation of the program itself, signed by the event authority.
+///
+/// # Panics
+///
+/// Panics if the buffer is smaller than
+/// `EVENT_IX_TAG_LE.len() + DISCRIMINATOR_LEN + E::DATA_LEN` bytes.
+#[inline]
+pub fn emit_cpi_event<E: Event>(
+    event: &E,
+    buffer: &mut [u8],
+    program_id: &Pubkey,
+    event_authority: &AccountInfo,
+    signer_seeds: &[Seed],
+) -> ProgramResult {
+    let buffer = &mut buffer[..EVENT_IX_TAG_LE.len() + DISCRIMINATOR_LEN + E::DATA_LEN];
+    let (tag, event_bytes) = buffer.split_at_mut(EVENT_IX_TAG_LE.len());
+    let (discriminator, data) = event_bytes.split_at_mut(DISCRIMINATOR_LEN);
+
+    tag.copy_from_slice(&EVENT_IX_TAG_LE);
+    discriminator.copy_from_slice(&E::DISCRIMINATOR);
+    event.write_data(data);
+
+    let instruction = Instruction {
+        program_id,
+        data: buffer,
+        accounts: &[AccountMeta::readonly_signer(event_authority.key())],
+    };
+
+    invoke_signed(
+        &instruction,
+        &[event_authority],
+        &[Signer::from(signer_seeds)],
+    )
+}
+
+/// Handles the self-invocation used to emit events with [`Event::emit_cpi`].
+///
+/// This function should be called at the start of the instruction processor. It
+/// returns `Ok(true)` when the instruction is a self-CPI event, in which case the
+/// processor should return early without processing the instruction; and `Ok(false)`
+/// otherwise.
+///
+/// The event instruction is validated by checking that its first account is the
+/// `event_authority` and that it signed the invocation, which can only happen when
+/// the program invokes itself. Otherwise, it returns a
+/// [`ProgramError::MissingRequiredSignature`] error.
+///
+/// # Example
+///
+/// ```ignore
+/// pinocchio_pubkey::declare_event_authority!(program = crate::ID);
+///
+/// pub fn process_instruction(
+///     program_id: &Pubkey,
+///     accounts: &[AccountInfo],
+///     instruction_data: &[u8],
+/// ) -> ProgramResult {
+///     if process_event_instruction(accounts, instruction_data, &EVENT_AUTHORITY::ADDRESS)? {
+///         return Ok(());
+///     }
+///     // ...
+/// }
+/// ```
+#[inline]
+pub fn process_event_instruction(
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+    event_authority: &Pubkey,
+) -> Result<bool, ProgramError> {
+    if !instruction_data.starts_with(&EVENT_IX_TAG_LE) {
+        return Ok(false);
+    }
+
+    match accounts.first() {
+        Some(account) if account.key() == event_authority && account.is_signer() => Ok(true),
+        Some(_) => Err(ProgramError::MissingRequiredSignature),
+        None => Err(ProgramError::NotEnoughAccountKeys),
+    }
+}
+
+/// Computes the discriminator of an event, i.e., the first 8 bytes of
+/// `sha256("event:<name>")`.
+///
+/// This is the same discriminator used by Anchor for events.
+pub const fn discriminator(name: &str) -> [u8; DISCRIMINATOR_LEN] {
+    let hash = sha256(&[b"event:", name.as_bytes()]);
+    let mut discriminator = [0; DISCRIMINATOR_LEN];
+    let mut i = 0;
+
+    while i < DISCRIMINATOR_LEN {
+        discriminator[i] = hash[i];
+        i += 1;
+    }
+
+    discriminator
+}
+
+/// Convenience macro to declare an event struct and implement the [`Event`] trait.
+///
+/// The struct fields must implement [`EventField`]. They are encoded in the order of
+/// declaration and the discriminator is computed from the struct name using
+/// [`discriminator`].
+///
+/// This is the declarative equivalent of Anchor's `#[event]` attribute: the struct
+/// is written inside the macro invocation instead of being annotated.
+///
+/// # Example
+///
+/// ```
+/// use pinocchio::{event, event::Event, pubkey::Pubkey};
+///
+/// event! {
+///     #[derive(Debug, PartialEq)]
+///     pub struct Deposit {
+///         pub owner: Pubkey,
+///         pub amount: u64,
+///     }
+/// }
+///
+/// let deposit = Deposit { owner: [7; 32], amount: 100 };
+/// deposit.emit();
+/// ```
+#[macro_export]
+macro_rules! event {
+    (
+        $(#[$meta:meta])*
+        $vis:vis struct $name:ident {
+            $( $(#[$field_meta:meta])* $field_vis:vis $field:ident : $type:ty ),* $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        $vis struct $name {
+            $( $(#[$field_meta])* $field_vis $field: $type, )*
+        }
+
+        impl $crate::event::Event for $name {
+            const DISCRIMINATOR: [u8; $crate::event::DISCRIMINATOR_LEN] =
+                $crate::event::discriminator(stringify!($name));
+
+            const DATA_LEN: usize = 0 $( + <$type as $crate::event::EventField>::LEN )*;
+
+            #[allow(unused_assignments, unused_mut, unused_variables)]
+            #[inline]
+            fn write_data(&self, buffer: &mut [u8]) {
+                let mut offset = 0;
+                $(
+                    let end = offset + <$type as $crate::event::EventField>::LEN;
+                    $crate::event::EventField::write(&self.$field, &mut buffer[offset..end]);
+                    offset = end;
+                )*
+            }
+
+            #[allow(unused_assignments, unused_mut, unused_variables)]
+            #[inline]
+            fn read_data(data: &[u8]) -> Option<Self> {
+                let mut offset = 0;
+                $(
+                    let end = offset + <$type as $crate::event::EventField>::LEN;
+                    let $field = <$type as $crate::event::EventField>::read(&data[offset..end])?;
+                    offset = end;
+                )*
+                Some(Self { $( $field, )* })
+            }
+
+            #[inline]
+            fn emit(&self) {
+                let mut buffer = [0u8; $crate::event::DISCRIMINATOR_LEN
+                    + <$name as $crate::event::Event>::DATA_LEN];
+                $crate::event::emit_event(self, &mut buffer);
+            }
+
+            #[inline]
+            fn emit_cpi(
+                &self,
+                program_id: &$crate::pubkey::Pubkey,
+                event_authority: &$crate::account_info::AccountInfo,
+                signer_seeds: &[$crate::instruction::Seed],
+            ) -> $crate::ProgramResult {
+                let mut buffer = [0u8; $crate::event::EVENT_IX_TAG_LE.len()
+                    + $crate::event::DISCRIMINATOR_LEN
+                    + <$name as $crate::event::Event>::DATA_LEN];
+                $crate::event::emit_cpi_event(
+                    self,
+                    &mut buffer,
+                    program_id,
+                    event_authority,
+                    signer_seeds,
+                )
+            }
+        }
+    };
+}
+
+/// Decodes an event from a `Program data:` log line.
+///
+/// The line is expected to have the format produced by `sol_log_data`, i.e., the
+/// `Program data: ` prefix followed by space-separated `base64` encoded slices. The
+/// decoded slices are concatenated before decoding the event, so events logged by
+/// Anchor programs can be decoded as well.
+///
+/// Returns `None` if the line is not a `Program data:` line or it does not contain
+/// an event of type `E`.
+#[cfg(feature = "std")]
+pub fn decode_log<E: Event>(line: &str) -> Option<E> {
+    let encoded = line.strip_prefix("Program data: ")?;
+    let mut bytes = std::vec::Vec::new();
+
+    for slice in encoded.split_ascii_whitespace() {
+        decode_base64(slice, &mut bytes)?;
+    }
+
+    E::from_bytes(&bytes)
+}
+
+/// Decodes a padded `base64` value, appending the bytes to the output.
+#[cfg(feature = "std")]
+fn decode_base64(value: &str, output: &mut std::vec::Vec<u8>) -> Option<()> {
+    fn sextet(character: u8) -> Option<u32> {
+        match character {
+            b'A'..=b'Z' => Some((character - b'A') as u32),
+            b'a'..=b'z' => Some((character - b'a' + 26) as u32),
+            b'0'..=b'9' => Some((character - b'0' + 52) as u32),
+            b'+' => Some(62),
+            b'/' => Some(63),
+            _ => None,
+        }
+    }
+
+    let value = value.as_bytes();
+
+    if value.len() % 4 != 0 {
+        return None;
+    }
+
+    for (i, chunk) in value.chunks_exact(4).enumerate() {
+        let last = i == value.len() / 4 - 1;
+        let padding = chunk.iter().rev().take_while(|c| **c == b'=').count();
+
+        if padding > 2 || (padding > 0 && !last) {
+            return None;
+        }
+
+        let mut group = 0;
+
+        for character in &chunk[..4 - padding] {
+            group = (group << 6) | sextet(*character)?;
+        }
+        group <<= 6 * padding;
+
+        output.extend_from_slice(&group.to_be_bytes()[1..4 - padding]);
+    }
+
+    Some(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pubkey::Pubkey;
+
+    crate::event! {
+        #[derive(Debug, PartialEq)]
+        struct Transfer {
+            from: Pubkey,
+            amount: u64,
+            settled: bool,
+        }
+    }
+
+    #[test]
+    fn test_discriminator() {
+        assert_eq!(discriminator(""), [151, 94, 61, 184, 50, 185, 202, 179]);
+    }
+
+    #[test]
+    fn test_event() {
+        assert_eq!(Transfer::DISCRIMINATOR, [25, 18, 23, 7, 172, 116, 130, 28]);
+        assert_eq!(Transfer::DATA_LEN, 41);
+
+        let transfer = Transfer {
+            from: [1; 32],
+            amount: 500,
+            settled: true,
+        };
+
+        let mut buffer = [0u8; 49];
+        emit_event(&transfer, &mut buffer);
+
+        assert_eq!(&buffer[..8], &Transfer::DISCRIMINATOR);
+        assert_eq!(&buffer[8..40], &[1; 32]);
+        assert_eq!(&buffer[40..48], &500u64.to_le_bytes());
+        assert_eq!(buffer[48], 1);
+
+        assert_eq!(Transfer::from_bytes(&buffer), Some(transfer));
+        // Invalid discriminator, length and field value.
+        assert_eq!(Transfer::from_bytes(&buffer[1..]), None);
+        buffer[0] = 0;
+        assert_eq!(Transfer::from_bytes(&buffer), None);
+        buffer[0] = Transfer::DISCRIMINATOR[0];
+        buffer[48] = 2;
+        assert_eq!(Transfer::from_bytes(&buffer), None);
+    }
+
+    #[test]
+    fn test_self_cpi_event() {
+        assert_eq!(EVENT_IX_TAG_LE, [228, 69, 165, 46, 81, 203, 154, 29]);
+
+        let mut data = [crate::account_info::Account::default(); 2];
+        let [authority, other] = [0, 1].map(|i| {
+            let raw = &mut data[i] as *mut crate::account_info::Account;
+            // SAFETY: `raw` points to a valid `Account`; offsets `0`, `1` and `8` are
+            // the `borrow_state`, `is_signer` and `key` fields.
+            unsafe {
+                (*raw).borrow_state = crate::NON_DUP_MARKER;
+                (raw as *mut u8).add(1).write(1);
+                (raw as *mut u8).add(8).write_bytes(i as u8 + 1, 32);
+            }
+            AccountInfo { raw }
+        });
+
+        let transfer = Transfer {
+            from: [1; 32],
+            amount: 500,
+            settled: false,
+        };
+
+        let mut buffer = [0u8; 57];
+        let bump = [255];
+        let seeds = [Seed::from(EVENT_AUTHORITY_SEED), Seed::from(&bump)];
+
+        assert!(emit_cpi_event(&transfer, &mut buffer, &[9; 32], &authority, &seeds).is_ok());
+        assert_eq!(&buffer[..8], &EVENT_IX_TAG_LE);
+        assert_eq!(Transfer::from_bytes(&buffer[8..]), Some(transfer));
+
+        // The event instruction is handled when the event authority signed it.
+        let accounts = [authority.clone(), other.clone()];
+        assert_eq!(
+            process_event_instruction(&accounts, &buffer, &[1; 32]),
+            Ok(true)
+        );
+        assert_eq!(
+            process_event_instruction(&accounts, &buffer, &[2; 32]),
+            Err(ProgramError::MissingRequiredSignature)
+        );
+        assert_eq!(
+            process_event_instruction(&[], &buffer, &[1; 32]),
+            Err(ProgramError::NotEnoughAccountKeys)
+        );
+        // Other instructions are not handled.
+        assert_eq!(
+            process_event_instruction(&accounts, &buffer[8..], &[1; 32]),
+            Ok(false)
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_decode_log() {
+        let line =
+            "Program data: GRIXB6x0ghwBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAfQBAAAAAAAAAQ==";
+
+        assert_eq!(
+            decode_log::<Transfer>(line),
+            Some(Transfer {
+                from: [1; 32],
+                amount: 500,
+                settled: true,
+            })
+        );
+
+        // The event split across multiple slices.
+        let line =
+            "Program data: GRIXB6x0ghw= AQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQH0AQAAAAAAAAE=";
+        assert!(decode_log::<Transfer>(line).is_some());
+
+        assert_eq!(decode_log::<Transfer>("Program log: hello"), None);
+        assert_eq!(decode_log::<Transfer>("Program data: GRIX*"), None);
+    }
+}
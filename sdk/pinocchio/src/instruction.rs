@@ -1,8 +1,14 @@
 //! Instruction types.
 
-use core::{marker::PhantomData, ops::Deref};
+use core::{marker::PhantomData, mem::MaybeUninit, ops::Deref};
 
-use crate::{account_info::AccountInfo, pubkey::Pubkey};
+use crate::{
+    account_info::AccountInfo,
+    cpi::{slice_invoke_signed, MAX_CPI_ACCOUNTS},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    ProgramResult,
+};
 
 /// Information about a CPI instruction.
 #[derive(Debug, Clone)]
@@ -20,6 +26,195 @@ where
     pub accounts: &'b [AccountMeta<'a>],
 }
 
+/// Builder for cross-program invocation instructions with a variable number of
+/// accounts and instruction data.
+///
+/// The builder stores up to `ACCOUNTS` accounts and `DATA` bytes of instruction
+/// data on the stack, so the capacity is fixed at compile time and no heap memory
+/// is used. Pushing a value beyond the capacity returns a
+/// [`ProgramError::InvalidArgument`] error.
+///
+/// # Example
+///
+/// ```ignore
+/// let mut builder = InstructionBuilder::<4, 9>::new(&program_id);
+///
+/// builder.push_account(from, true, true)?.push_account(to, true, false)?;
+/// builder.push_u8(2)?.push_u64(lamports)?;
+///
+/// builder.invoke()?;
+/// ```
+pub struct InstructionBuilder<'a, const ACCOUNTS: usize, const DATA: usize> {
+    /// Public key of the program.
+    program_id: &'a Pubkey,
+
+    /// Metadata of the accounts of the instruction.
+    metas: [MaybeUninit<AccountMeta<'a>>; ACCOUNTS],
+
+    /// Accounts of the instruction, in the same order as `metas`.
+    accounts: [MaybeUninit<&'a AccountInfo>; ACCOUNTS],
+
+    /// Number of accounts pushed.
+    accounts_len: usize,
+
+    /// Instruction data.
+    data: [MaybeUninit<u8>; DATA],
+
+    /// Number of bytes of instruction data pushed.
+    data_len: usize,
+}
+
+impl<'a, const ACCOUNTS: usize, const DATA: usize> InstructionBuilder<'a, ACCOUNTS, DATA> {
+    /// Creates a new empty `InstructionBuilder` for the given program.
+    #[inline(always)]
+    pub fn new(program_id: &'a Pubkey) -> Self {
+        const {
+            assert!(
+                ACCOUNTS <= MAX_CPI_ACCOUNTS,
+                "number of accounts must not exceed MAX_CPI_ACCOUNTS"
+            );
+        }
+
+        Self {
+            program_id,
+            metas: [const { MaybeUninit::uninit() }; ACCOUNTS],
+            accounts: [const { MaybeUninit::uninit() }; ACCOUNTS],
+            accounts_len: 0,
+            data: [const { MaybeUninit::uninit() }; DATA],
+            data_len: 0,
+        }
+    }
+
+    /// Pushes an account with the given writable and signer flags.
+    #[inline]
+    pub fn push_account(
+        &mut self,
+        account: &'a AccountInfo,
+        is_writable: bool,
+        is_signer: bool,
+    ) -> Result<&mut Self, ProgramError> {
+        self.push_meta(
+            AccountMeta::new(account.key(), is_writable, is_signer),
+            account,
+        )
+    }
+
+    /// Pushes an account metadata and its corresponding account.
+    ///
+    /// The key of the account must match the `pubkey` of the metadata, which is
+    /// validated when the instruction is invoked.
+    #[inline]
+    pub fn push_meta(
+        &mut self,
+        meta: AccountMeta<'a>,
+        account: &'a AccountInfo,
+    ) -> Result<&mut Self, ProgramError> {
+        if self.accounts_len == ACCOUNTS {
+            return Err(ProgramError::InvalidArgument);
+        }
+
+        self.metas[self.accounts_len].write(meta);
+        self.accounts[self.accounts_len].write(account);
+        self.accounts_len += 1;
+
+        Ok(self)
+    }
+
+    /// Pushes bytes to the instruction data.
+    #[inline]
+    pub fn push_data(&mut self, data: &[u8]) -> Result<&mut Self, ProgramError> {
+        let end = self
+            .data_len
+            .checked_add(data.len())
+            .filter(|end| *end <= DATA)
+            .ok_or(ProgramError::InvalidArgument)?;
+
+        for (destination, byte) in self.data[self.data_len..end].iter_mut().zip(data) {
+            destination.write(*byte);
+        }
+        self.data_len = end;
+
+        Ok(self)
+    }
+
+    /// Pushes a `u8` value to the instruction data.
+    #[inline(always)]
+    pub fn push_u8(&mut self, value: u8) -> Result<&mut Self, ProgramError> {
+        self.push_data(&[value])
+    }
+
+    /// Pushes a `u16` value to the instruction data in little-endian order.
+    #[inline(always)]
+    pub fn push_u16(&mut self, value: u16) -> Result<&mut Self, ProgramError> {
+        self.push_data(&value.to_le_bytes())
+    }
+
+    /// Pushes a `u32` value to the instruction data in little-endian order.
+    #[inline(always)]
+    pub fn push_u32(&mut self, value: u32) -> Result<&mut Self, ProgramError> {
+        self.push_data(&value.to_le_bytes())
+    }
+
+    /// Pushes a `u64` value to the instruction data in little-endian order.
+    #[inline(always)]
+    pub fn push_u64(&mut self, value: u64) -> Result<&mut Self, ProgramError> {
+        self.push_data(&value.to_le_bytes())
+    }
+
+    /// Returns the account metadata pushed so far.
+    #[inline(always)]
+    pub fn metas(&self) -> &[AccountMeta<'a>] {
+        // SAFETY: The first `accounts_len` metas have been initialized.
+        unsafe {
+            core::slice::from_raw_parts(
+                self.metas.as_ptr() as *const AccountMeta,
+                self.accounts_len,
+            )
+        }
+    }
+
+    /// Returns the accounts pushed so far.
+    #[inline(always)]
+    pub fn accounts(&self) -> &[&'a AccountInfo] {
+        // SAFETY: The first `accounts_len` accounts have been initialized.
+        unsafe {
+            core::slice::from_raw_parts(
+                self.accounts.as_ptr() as *const &AccountInfo,
+                self.accounts_len,
+            )
+        }
+    }
+
+    /// Returns the instruction data pushed so far.
+    #[inline(always)]
+    pub fn data(&self) -> &[u8] {
+        // SAFETY: The first `data_len` bytes have been initialized.
+        unsafe { core::slice::from_raw_parts(self.data.as_ptr() as *const u8, self.data_len) }
+    }
+
+    /// Returns the `Instruction` represented by the builder.
+    #[inline(always)]
+    pub fn instruction(&self) -> Instruction<'a, '_, 'a, '_> {
+        Instruction {
+            program_id: self.program_id,
+            data: self.data(),
+            accounts: self.metas(),
+        }
+    }
+
+    /// Invokes the instruction.
+    #[inline(always)]
+    pub fn invoke(&self) -> ProgramResult {
+        self.invoke_signed(&[])
+    }
+
+    /// Invokes the instruction with the given signers.
+    #[inline(always)]
+    pub fn invoke_signed(&self, signers: &[Signer]) -> ProgramResult {
+        slice_invoke_signed(&self.instruction(), self.accounts(), signers)
+    }
+}
+
 /// Use to query and convey information about the sibling instruction components
 /// when calling the `sol_get_processed_sibling_instruction` syscall.
 #[repr(C)]
@@ -114,6 +309,17 @@ impl<'a> From<&'a AccountInfo> for Account<'a> {
     }
 }
 
+impl Account<'_> {
+    /// Updates the data length from the current length of the account.
+    ///
+    /// This is necessary when an `Account` is reused across invocations, since the
+    /// length of the account data might change between them.
+    #[inline(always)]
+    pub(crate) fn sync_data_len(&mut self, account: &AccountInfo) {
+        self.data_len = account.data_len() as u64;
+    }
+}
+
 /// Describes a single account read or written by a program during instruction
 /// execution.
 ///
@@ -201,6 +407,20 @@ pub struct Seed<'a> {
     _bytes: PhantomData<&'a [u8]>,
 }
 
+impl<'a> Seed<'a> {
+    /// Creates a new `Seed` from the seed bytes.
+    ///
+    /// This is the same as `Seed::from`, but it can be used in `const` contexts.
+    #[inline(always)]
+    pub const fn new(seed: &'a [u8]) -> Self {
+        Self {
+            seed: seed.as_ptr(),
+            len: seed.len() as u64,
+            _bytes: PhantomData::<&[u8]>,
+        }
+    }
+}
+
 impl<'a> From<&'a [u8]> for Seed<'a> {
     fn from(value: &'a [u8]) -> Self {
         Self {
@@ -269,6 +489,75 @@ impl<'a, 'b, const SIZE: usize> From<&'b [Seed<'a>; SIZE]> for Signer<'a, 'b> {
     }
 }
 
+impl<'a> Signer<'a, '_> {
+    /// Creates the signer seeds for a PDA from its seeds and bump seed.
+    ///
+    /// The bump seed is appended to the seeds without copying it into a temporary
+    /// array, so a reference to a `u8` value can be used directly. Use `Signer::from`
+    /// on the returned [`SignerSeeds`] to obtain the `Signer`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pinocchio::{seeds, instruction::Signer};
+    /// use pinocchio::pubkey::Pubkey;
+    ///
+    /// let pda_bump = 0xffu8;
+    /// let example_key = Pubkey::default();
+    /// let seeds = Signer::with_bump(seeds!(b"seed", &example_key), &pda_bump);
+    /// let signer = Signer::from(&seeds);
+    /// ```
+    #[inline(always)]
+    pub fn with_bump<const SIZE: usize>(
+        seeds: [Seed<'a>; SIZE],
+        bump: &'a u8,
+    ) -> SignerSeeds<'a, SIZE> {
+        SignerSeeds::new(seeds, bump)
+    }
+}
+
+/// Seeds of a [program derived address][pda] followed by its bump seed.
+///
+/// The bump seed is stored right after the seeds, so the type has the same memory
+/// layout as a `[Seed; SIZE + 1]` array.
+///
+/// [pda]: https://solana.com/docs/core/cpi#program-derived-addresses
+#[repr(C)]
+#[derive(Debug, Clone)]
+pub struct SignerSeeds<'a, const SIZE: usize> {
+    /// Signer seeds, excluding the bump seed.
+    seeds: [Seed<'a>; SIZE],
+
+    /// Bump seed.
+    bump: Seed<'a>,
+}
+
+impl<'a, const SIZE: usize> SignerSeeds<'a, SIZE> {
+    /// Creates a new `SignerSeeds` from the seeds and bump seed.
+    #[inline(always)]
+    pub const fn new(seeds: [Seed<'a>; SIZE], bump: &'a u8) -> Self {
+        Self {
+            seeds,
+            bump: Seed::new(core::slice::from_ref(bump)),
+        }
+    }
+
+    /// Returns the seeds, including the bump seed.
+    #[inline(always)]
+    pub fn as_slice(&self) -> &[Seed<'a>] {
+        // SAFETY: The struct is `repr(C)` and the `bump` field has the same type as the
+        // elements of the `seeds` array, so there is no padding between them. The pointer
+        // is derived from the whole struct, so it is valid for both fields.
+        unsafe { core::slice::from_raw_parts(self as *const Self as *const Seed<'a>, SIZE + 1) }
+    }
+}
+
+impl<'a, 'b, const SIZE: usize> From<&'b SignerSeeds<'a, SIZE>> for Signer<'a, 'b> {
+    fn from(value: &'b SignerSeeds<'a, SIZE>) -> Self {
+        Self::from(value.as_slice())
+    }
+}
+
 /// Convenience macro for constructing a `Signer` from a list of seeds
 /// represented as byte slices.
 ///
@@ -314,3 +603,73 @@ macro_rules! seeds {
         )*]
     };
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::account_info::Account;
+
+    #[test]
+    fn test_signer_with_bump() {
+        let key = [7u8; 32];
+        let bump = 254u8;
+
+        let seeds = Signer::with_bump(crate::seeds!(b"vault", &key), &bump);
+        let slice = seeds.as_slice();
+
+        assert_eq!(slice.len(), 3);
+        assert_eq!(&*slice[0], b"vault");
+        assert_eq!(&*slice[1], &key);
+        assert_eq!(&*slice[2], &[254]);
+
+        let signer = Signer::from(&seeds);
+
+        assert_eq!(signer.len, 3);
+        assert_eq!(signer.seeds, slice.as_ptr());
+    }
+
+    #[test]
+    fn test_instruction_builder() {
+        // Account data for two accounts, 8-byte aligned.
+        let mut data = [0u64; 2 * core::mem::size_of::<Account>() / 8];
+        let raw = data.as_mut_ptr() as *mut Account;
+
+        let accounts = [0, 1].map(|i| unsafe {
+            let account = raw.add(i);
+            // Offset `8` is the `key` field in the `Account` struct.
+            (account as *mut u8).add(8).write_bytes(i as u8, 32);
+            AccountInfo { raw: account }
+        });
+
+        let program_id = [9; 32];
+        let mut builder = InstructionBuilder::<2, 5>::new(&program_id);
+
+        builder
+            .push_account(&accounts[0], true, true)
+            .unwrap()
+            .push_meta(AccountMeta::readonly(accounts[1].key()), &accounts[1])
+            .unwrap();
+        builder.push_u8(2).unwrap().push_u32(0x01020304).unwrap();
+
+        // The capacity is exhausted.
+        assert_eq!(
+            builder.push_account(&accounts[1], false, false).err(),
+            Some(ProgramError::InvalidArgument)
+        );
+        assert_eq!(
+            builder.push_u8(0).err(),
+            Some(ProgramError::InvalidArgument)
+        );
+
+        let instruction = builder.instruction();
+
+        assert_eq!(instruction.program_id, &program_id);
+        assert_eq!(instruction.data, &[2, 4, 3, 2, 1]);
+        assert_eq!(instruction.accounts.len(), 2);
+        assert_eq!(instruction.accounts[0].pubkey, &[0; 32]);
+        assert!(instruction.accounts[0].is_writable && instruction.accounts[0].is_signer);
+        assert_eq!(instruction.accounts[1].pubkey, &[1; 32]);
+        assert!(!instruction.accounts[1].is_writable && !instruction.accounts[1].is_signer);
+        assert_eq!(builder.accounts().len(), 2);
+    }
+}
@@ -122,6 +122,11 @@
 //! * [`program_id()`](entrypoint::InstructionContext::program_id): parses the
 //!   program id.
 //!
+//! The [`data_first_program_entrypoint!`] is a variant of the lazy entrypoint where
+//! the instruction data and program id are available before any account is parsed.
+//! This is useful to dispatch on the instruction discriminator and only parse the
+//! accounts each instruction needs.
+//!
 //!
 //! 💡 The [`lazy_program_entrypoint!`] does not set up a global allocator nor a panic
 //! handler. A program should explicitly use one of the provided macros to set them
@@ -163,6 +168,23 @@
 //! 💡 The [`no_allocator!`] macro can also be used in combination with the
 //! [`lazy_program_entrypoint!`].
 //!
+//! ### [`custom_allocator!`]
+//!
+//! The [`default_allocator!`] sets up a bump allocator that never frees memory.
+//! Programs that allocate and deallocate memory repeatedly can use the
+//! [`custom_allocator!`] macro to select a different allocator:
+//!
+//! * `stack`: reclaims the most recent allocation and resizes it in place, which
+//!   avoids leaking memory when growing a `Vec`. It also defines a `heap_scope`
+//!   function to release all memory allocated within a closure.
+//! * `free_list`: reuses deallocated memory of small allocations.
+//!
+//! ```ignore
+//! program_entrypoint!(process_instruction);
+//! custom_allocator!(stack);
+//! default_panic_handler!();
+//! ```
+//!
 //! ## `std` crate feature
 //!
 //! By default, Pinocchio is a `no_std` crate. This means that it does not use any
@@ -180,6 +202,38 @@
 //! crate. This crate provides a lightweight `log!` macro with better compute units
 //! consumption than the standard `format!` macro without requiring the `std` library.
 //!
+//! The `std` feature also enables [`event::decode_log`], which decodes events emitted
+//! with [`event!`] from `Program data:` log lines, e.g., in indexers and tests.
+//!
+//! ## `diagnostics` crate feature
+//!
+//! The allocator macros ([`default_allocator!`] and [`custom_allocator!`]) define a
+//! `log_heap_usage` function that logs the number of bytes in use, the peak usage and
+//! the remaining bytes of the heap. Similarly, [`entrypoint::log_stack_usage`] logs the
+//! approximate depth of the call stack. These functions only log messages when the
+//! `diagnostics` feature is enabled, so calls to them can be left in place and enabled
+//! on specific builds. The feature also makes the [`program_entrypoint!`] check that a
+//! successful instruction does not change the total lamports of its accounts, panicking
+//! otherwise:
+//! ```ignore
+//! pinocchio = { version = "0.9.0", features = ["diagnostics"] }
+//! ```
+//!
+//! ## Log level crate features
+//!
+//! Messages logged with a [`log::Level`] (e.g., `msg!(Level::Debug, "...")`) are only
+//! logged when their level is enabled. The maximum level is selected at compile time
+//! with one of the `max_level_off`, `max_level_error`, `max_level_warn`, `max_level_info`,
+//! `max_level_debug` and `max_level_trace` features &mdash; calls of disabled levels are
+//! removed, so they do not consume compute units. All levels are enabled by default.
+//! For example, to only log errors and warnings on a mainnet build:
+//! ```ignore
+//! pinocchio = { version = "0.9.0", features = ["max_level_warn"] }
+//! ```
+//!
+//! The same levels are used by the leveled macros of `pinocchio-log`, which enables the
+//! `max_level_*` features of this crate when its optional `pinocchio` feature is enabled.
+//!
 //! ## Advanced entrypoint configuration
 //!
 //! The symbols emitted by the entrypoint macros - program entrypoint, global
@@ -225,7 +279,9 @@ extern crate std;
 
 pub mod account_info;
 pub mod cpi;
+mod curve;
 pub mod entrypoint;
+pub mod event;
 pub mod instruction;
 pub mod log;
 pub mod memory;
@@ -235,6 +291,7 @@ pub mod program {
 }
 pub mod program_error;
 pub mod pubkey;
+mod sha256;
 pub mod syscalls;
 pub mod sysvars;
 
@@ -32,6 +32,10 @@
 //!
 //! [`Pubkey`]: crate::pubkey::Pubkey
 //! [`pubkey::log`]: crate::pubkey::log
+//!
+//! Messages can also be logged at a [`Level`] by passing it as the first argument
+//! of [`msg!`]. These messages are only logged when the level is enabled by the
+//! [`MAX_LEVEL`], selected at compile time with the `max_level_*` crate features.
 
 use crate::{account_info::AccountInfo, pubkey};
 
@@ -40,16 +44,27 @@ use crate::{account_info::AccountInfo, pubkey};
 /// Supports simple strings of type `&str`. The expression will be passed
 /// directly to [`sol_log`]. This is typically used for logging static strings.
 ///
+/// The message can be preceded by a [`Level`], in which case it is only logged
+/// when the level is enabled by the [`MAX_LEVEL`].
+///
 /// # Examples
 ///
 /// ```
-/// use pinocchio::msg;
+/// use pinocchio::{log::Level, msg};
 ///
 /// msg!("verifying multisig");
+///
+/// // Removed when the maximum level is `info` or lower.
+/// msg!(Level::Debug, "verifying signers");
 /// ```
 #[macro_export]
 #[cfg(not(feature = "std"))]
 macro_rules! msg {
+    ( $level:path, $msg:expr ) => {
+        if const { $crate::log::Level::is_enabled($level) } {
+            $crate::log::sol_log($msg)
+        }
+    };
     ( $msg:expr ) => {
         $crate::log::sol_log($msg)
     };
@@ -70,10 +85,14 @@ macro_rules! msg {
 /// Note that Rust's formatting machinery is relatively CPU-intensive
 /// for constrained environments like the Solana VM.
 ///
+/// The message can be preceded by a [`Level`], in which case it is only logged
+/// when the level is enabled by the [`MAX_LEVEL`]. The message is not formatted
+/// when the level is disabled.
+///
 /// # Examples
 ///
 /// ```
-/// use pinocchio::msg;
+/// use pinocchio::{log::Level, msg};
 ///
 /// // The fast form
 /// msg!("verifying multisig");
@@ -81,16 +100,75 @@ macro_rules! msg {
 /// // With formatting
 /// let err = "not enough signers";
 /// msg!("multisig failed: {}", err);
+///
+/// // Removed when the maximum level is `info` or lower.
+/// msg!(Level::Debug, "signers: {}", 2);
 /// ```
 #[cfg(feature = "std")]
 #[macro_export]
 macro_rules! msg {
+    ( $level:path, $( $arg:tt )+ ) => {
+        if const { $crate::log::Level::is_enabled($level) } {
+            $crate::msg!($( $arg )+)
+        }
+    };
     ( $msg:expr ) => {
         $crate::log::sol_log($msg)
     };
     ( $( $arg:tt )* ) => ($crate::log::sol_log(&format!($($arg)*)));
 }
 
+/// Log levels, ordered from the least to the most verbose.
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    /// Errors that prevent the program from completing an operation.
+    Error = 1,
+
+    /// Unexpected conditions that do not prevent the program from completing
+    /// an operation.
+    Warn,
+
+    /// Information about the execution of the program.
+    Info,
+
+    /// Detailed information useful when debugging the program.
+    Debug,
+
+    /// Very detailed information about the execution of the program.
+    Trace,
+}
+
+impl Level {
+    /// Check whether the level is enabled by the [`MAX_LEVEL`] or not.
+    #[inline(always)]
+    pub const fn is_enabled(self) -> bool {
+        match MAX_LEVEL {
+            Some(max_level) => self as u8 <= max_level as u8,
+            None => false,
+        }
+    }
+}
+
+/// Maximum log level enabled at compile time.
+///
+/// The level is selected with one of the `max_level_*` crate features, using the
+/// most restrictive one when more than one is enabled. All levels are enabled by
+/// default, while `max_level_off` disables leveled messages.
+pub const MAX_LEVEL: Option<Level> = if cfg!(feature = "max_level_off") {
+    None
+} else if cfg!(feature = "max_level_error") {
+    Some(Level::Error)
+} else if cfg!(feature = "max_level_warn") {
+    Some(Level::Warn)
+} else if cfg!(feature = "max_level_info") {
+    Some(Level::Info)
+} else if cfg!(feature = "max_level_debug") {
+    Some(Level::Debug)
+} else {
+    Some(Level::Trace)
+};
+
 /// Print a string to the log.
 #[inline(always)]
 pub fn sol_log(message: &str) {
@@ -1,6 +1,8 @@
 //! Public key type and functions.
 
-use crate::program_error::ProgramError;
+use core::mem::MaybeUninit;
+
+use crate::{account_info::AccountInfo, program_error::ProgramError};
 
 /// Number of bytes in a pubkey.
 pub const PUBKEY_BYTES: usize = 32;
@@ -33,6 +35,18 @@ pub fn log(pubkey: &Pubkey) {
     core::hint::black_box(pubkey);
 }
 
+/// Check whether the address is a point on the ed25519 curve.
+///
+/// Addresses on the curve have an associated private key, so a
+/// [program derived address][pda] must not lie on the curve. This is evaluated
+/// without syscalls and can be used in constant expressions.
+///
+/// [pda]: https://solana.com/docs/core/pda
+#[inline]
+pub const fn is_on_curve(address: &Pubkey) -> bool {
+    crate::curve::is_on_curve(address)
+}
+
 /// Find a valid [program derived address][pda] and its corresponding bump seed.
 ///
 /// [pda]: https://solana.com/docs/core/cpi#program-derived-addresses
@@ -199,8 +213,26 @@ pub fn create_program_address(
 
     #[cfg(not(target_os = "solana"))]
     {
-        core::hint::black_box((seeds, program_id));
-        panic!("create_program_address is only available on target `solana`")
+        if seeds.len() > MAX_SEEDS {
+            return Err(ProgramError::MaxSeedLengthExceeded);
+        }
+
+        let mut buffer = [MaybeUninit::<&[u8]>::uninit(); MAX_SEEDS + 2];
+
+        for (slot, seed) in buffer.iter_mut().zip(seeds.iter()) {
+            if seed.len() > MAX_SEED_LEN {
+                return Err(ProgramError::MaxSeedLengthExceeded);
+            }
+            slot.write(seed);
+        }
+
+        let address = derive_address(&mut buffer, seeds.len(), program_id);
+
+        if is_on_curve(&address) {
+            Err(ProgramError::InvalidSeeds)
+        } else {
+            Ok(address)
+        }
     }
 }
 
@@ -237,3 +269,366 @@ pub fn checked_create_program_address(
 
     create_program_address(seeds, program_id)
 }
+
+/// Verify that an account address is the [program derived address][pda] of the
+/// given seeds and bump seed.
+///
+/// [pda]: https://solana.com/docs/core/cpi#program-derived-addresses
+///
+/// The address is derived by hashing the seeds, bump seed, program id and
+/// [`PDA_MARKER`] with the `sol_sha256` syscall, without checking whether the
+/// resulting address lies on the ed25519 curve. This is considerably cheaper than
+/// [`create_program_address`] and is safe for verification purposes: since the
+/// account address is given, finding seeds that hash to an on-curve address equal
+/// to it would require a preimage of the hash.
+///
+/// Returns [`ProgramError::InvalidSeeds`] when the derived address does not match
+/// the account address, and [`ProgramError::MaxSeedLengthExceeded`] when the number
+/// of seeds (including the bump seed) is greater than [`MAX_SEEDS`] or any seed is
+/// longer than [`MAX_SEED_LEN`].
+#[inline]
+pub fn verify_pda(
+    account: &AccountInfo,
+    seeds: &[&[u8]],
+    bump: u8,
+    program_id: &Pubkey,
+) -> Result<(), ProgramError> {
+    let mut buffer = [MaybeUninit::<&[u8]>::uninit(); MAX_SEEDS + 2];
+    let length = seeds_with_bump(seeds, &bump, &mut buffer)?;
+
+    if derive_address(&mut buffer, length, program_id) == *account.key() {
+        Ok(())
+    } else {
+        Err(ProgramError::InvalidSeeds)
+    }
+}
+
+/// Verify that an account address is the [program derived address][pda] of the
+/// given seeds and that the bump seed is the canonical one.
+///
+/// [pda]: https://solana.com/docs/core/cpi#program-derived-addresses
+///
+/// The canonical bump seed is the largest bump seed that derives a valid program
+/// address, which is the one returned by [`find_program_address`]. After verifying
+/// the address with [`verify_pda`], this function checks that every larger bump
+/// seed derives an address on the ed25519 curve using [`create_program_address`].
+/// The cost is therefore proportional to `255 - bump`, which is typically small.
+///
+/// Returns [`ProgramError::InvalidSeeds`] when the derived address does not match
+/// the account address or the bump seed is not canonical.
+#[inline]
+pub fn verify_canonical_pda(
+    account: &AccountInfo,
+    seeds: &[&[u8]],
+    bump: u8,
+    program_id: &Pubkey,
+) -> Result<(), ProgramError> {
+    verify_pda(account, seeds, bump, program_id)?;
+
+    let mut larger = bump;
+
+    while larger < u8::MAX {
+        larger += 1;
+
+        let mut buffer = [MaybeUninit::<&[u8]>::uninit(); MAX_SEEDS + 2];
+        let length = seeds_with_bump(seeds, &larger, &mut buffer)?;
+        // SAFETY: The first `length` slots of the buffer have been initialized.
+        let seeds = unsafe { core::slice::from_raw_parts(buffer.as_ptr() as *const &[u8], length) };
+
+        if create_program_address(seeds, program_id).is_ok() {
+            return Err(ProgramError::InvalidSeeds);
+        }
+    }
+
+    Ok(())
+}
+
+/// Create an address derived from a base address, a seed and an owner program.
+///
+/// The address is the SHA-256 hash of `base || seed || owner`, computed with the
+/// `sol_sha256` syscall on-chain. These addresses are used by the System program
+/// `*WithSeed` instructions, e.g., `CreateAccountWithSeed`, and do not require a
+/// signature from the derived address itself, only from the base address.
+///
+/// Returns [`ProgramError::MaxSeedLengthExceeded`] if the seed is longer than
+/// [`MAX_SEED_LEN`], and [`ProgramError::IllegalOwner`] if the owner ends with the
+/// [`PDA_MARKER`], since the resulting address could collide with a program
+/// derived address.
+#[inline]
+pub fn create_with_seed(base: &Pubkey, seed: &str, owner: &Pubkey) -> Result<Pubkey, ProgramError> {
+    if seed.len() > MAX_SEED_LEN {
+        return Err(ProgramError::MaxSeedLengthExceeded);
+    }
+
+    if owner.ends_with(PDA_MARKER) {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    #[cfg(target_os = "solana")]
+    {
+        let data: [&[u8]; 3] = [base.as_ref(), seed.as_bytes(), owner.as_ref()];
+        let mut address = MaybeUninit::<Pubkey>::uninit();
+
+        // SAFETY: `data` is a valid array of byte slices and `address` has room for
+        // the 32-byte hash.
+        unsafe {
+            crate::syscalls::sol_sha256(
+                data.as_ptr() as *const u8,
+                data.len() as u64,
+                address.as_mut_ptr() as *mut u8,
+            );
+            Ok(address.assume_init())
+        }
+    }
+
+    #[cfg(not(target_os = "solana"))]
+    Ok(crate::sha256::sha256(&[base, seed.as_bytes(), owner]))
+}
+
+/// Verify that an account address is derived from a base address, a seed and an
+/// owner program.
+///
+/// This is useful to validate the accounts passed to the System program `*WithSeed`
+/// instructions. See [`create_with_seed`] for details on how the address is derived.
+///
+/// Returns [`ProgramError::InvalidSeeds`] when the derived address does not match
+/// the account address.
+#[inline]
+pub fn verify_address_with_seed(
+    account: &AccountInfo,
+    base: &Pubkey,
+    seed: &str,
+    owner: &Pubkey,
+) -> Result<(), ProgramError> {
+    if create_with_seed(base, seed, owner)? == *account.key() {
+        Ok(())
+    } else {
+        Err(ProgramError::InvalidSeeds)
+    }
+}
+
+/// Validates the seeds and writes them to the buffer followed by the bump seed,
+/// returning the number of slots initialized.
+///
+/// The buffer has room for two additional slices, which are used by
+/// [`derive_address`] to append the program id and the PDA marker.
+#[inline(always)]
+fn seeds_with_bump<'a>(
+    seeds: &[&'a [u8]],
+    bump: &'a u8,
+    buffer: &mut [MaybeUninit<&'a [u8]>; MAX_SEEDS + 2],
+) -> Result<usize, ProgramError> {
+    if seeds.len() >= MAX_SEEDS {
+        return Err(ProgramError::MaxSeedLengthExceeded);
+    }
+
+    for (slot, seed) in buffer.iter_mut().zip(seeds.iter()) {
+        if seed.len() > MAX_SEED_LEN {
+            return Err(ProgramError::MaxSeedLengthExceeded);
+        }
+        slot.write(seed);
+    }
+
+    buffer[seeds.len()].write(core::slice::from_ref(bump));
+
+    Ok(seeds.len() + 1)
+}
+
+/// Derive a program address from the first `length` seeds in the buffer (including
+/// the bump seed) and program id, without checking whether it lies on the ed25519
+/// curve.
+#[inline(always)]
+fn derive_address<'a>(
+    buffer: &mut [MaybeUninit<&'a [u8]>; MAX_SEEDS + 2],
+    length: usize,
+    program_id: &'a Pubkey,
+) -> Pubkey {
+    buffer[length].write(program_id.as_ref());
+    buffer[length + 1].write(PDA_MARKER.as_ref());
+
+    #[cfg(target_os = "solana")]
+    {
+        let mut pda = MaybeUninit::<Pubkey>::uninit();
+
+        // SAFETY: The first `length + 2` slots of the buffer have been initialized
+        // and `pda` has room for the 32-byte hash.
+        unsafe {
+            crate::syscalls::sol_sha256(
+                buffer.as_ptr() as *const u8,
+                (length + 2) as u64,
+                pda.as_mut_ptr() as *mut u8,
+            );
+            pda.assume_init()
+        }
+    }
+
+    #[cfg(not(target_os = "solana"))]
+    {
+        // SAFETY: The first `length + 2` slots of the buffer have been initialized.
+        let parts =
+            unsafe { core::slice::from_raw_parts(buffer.as_ptr() as *const &[u8], length + 2) };
+        crate::sha256::sha256(parts)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::account_info::Account;
+    use core::mem::size_of;
+
+    const PROGRAM_ID: Pubkey = [7; 32];
+
+    /// Canonical address for the `b"vault"` seed and [`PROGRAM_ID`], with bump 254.
+    const VAULT: Pubkey = [
+        63, 204, 154, 156, 44, 128, 245, 147, 167, 68, 46, 138, 245, 247, 248, 127, 186, 116, 98,
+        91, 132, 36, 242, 186, 228, 156, 78, 10, 164, 22, 194, 236,
+    ];
+
+    fn with_account<R>(address: Pubkey, f: impl FnOnce(&AccountInfo) -> R) -> R {
+        // 8-bytes aligned account data.
+        let mut data = [0u64; size_of::<Account>() / size_of::<u64>()];
+        // The key is stored after the borrow state, duplicate flags and padding.
+        // SAFETY: The buffer is large enough to hold an `Account`.
+        unsafe {
+            (data.as_mut_ptr() as *mut u8)
+                .add(8)
+                .cast::<Pubkey>()
+                .write(address)
+        };
+        let account_info = AccountInfo {
+            raw: data.as_mut_ptr() as *mut Account,
+        };
+        f(&account_info)
+    }
+
+    #[test]
+    fn test_create_program_address() {
+        assert_eq!(
+            create_program_address(&[b"vault", &[254]], &PROGRAM_ID),
+            Ok(VAULT)
+        );
+        // Bump 255 derives an address on the curve.
+        assert_eq!(
+            create_program_address(&[b"vault", &[255]], &PROGRAM_ID),
+            Err(ProgramError::InvalidSeeds)
+        );
+    }
+
+    #[test]
+    fn test_verify_pda() {
+        with_account(VAULT, |account| {
+            assert_eq!(verify_pda(account, &[b"vault"], 254, &PROGRAM_ID), Ok(()));
+            assert_eq!(
+                verify_canonical_pda(account, &[b"vault"], 254, &PROGRAM_ID),
+                Ok(())
+            );
+
+            assert_eq!(
+                verify_pda(account, &[b"vault"], 253, &PROGRAM_ID),
+                Err(ProgramError::InvalidSeeds)
+            );
+            assert_eq!(
+                verify_pda(account, &[b"vault"], 254, &[8; 32]),
+                Err(ProgramError::InvalidSeeds)
+            );
+            assert_eq!(
+                verify_canonical_pda(account, &[b"vault"], 254, &[8; 32]),
+                Err(ProgramError::InvalidSeeds)
+            );
+        });
+
+        // A valid, but non-canonical, bump seed.
+        let (address, bump) = (0..254)
+            .rev()
+            .find_map(|bump| {
+                create_program_address(&[b"vault", &[bump]], &PROGRAM_ID)
+                    .ok()
+                    .map(|address| (address, bump))
+            })
+            .unwrap();
+
+        with_account(address, |account| {
+            assert_eq!(verify_pda(account, &[b"vault"], bump, &PROGRAM_ID), Ok(()));
+            assert_eq!(
+                verify_canonical_pda(account, &[b"vault"], bump, &PROGRAM_ID),
+                Err(ProgramError::InvalidSeeds)
+            );
+        });
+    }
+
+    #[test]
+    fn test_seeds_with_bump() {
+        let mut buffer = [MaybeUninit::<&[u8]>::uninit(); MAX_SEEDS + 2];
+        let bump = 255;
+
+        assert_eq!(
+            seeds_with_bump(&[b"vault", &[1; 32]], &bump, &mut buffer),
+            Ok(3)
+        );
+        // SAFETY: The first 3 slots have been initialized.
+        let seeds = unsafe { core::slice::from_raw_parts(buffer.as_ptr() as *const &[u8], 3) };
+        assert_eq!(seeds, &[b"vault".as_ref(), &[1; 32], &[255]]);
+
+        // The bump seed counts towards the maximum number of seeds.
+        assert_eq!(
+            seeds_with_bump(&[b"seed".as_ref(); MAX_SEEDS], &bump, &mut buffer),
+            Err(ProgramError::MaxSeedLengthExceeded)
+        );
+        assert_eq!(
+            seeds_with_bump(&[&[0; MAX_SEED_LEN + 1]], &bump, &mut buffer),
+            Err(ProgramError::MaxSeedLengthExceeded)
+        );
+    }
+
+    /// Address derived from the base `[1; 32]`, the seed `"seed"` and the owner `[2; 32]`.
+    const SEEDED: Pubkey = [
+        190, 17, 200, 199, 90, 126, 37, 199, 227, 150, 44, 113, 236, 142, 120, 63, 9, 253, 29, 91,
+        203, 57, 29, 232, 77, 141, 143, 38, 40, 3, 70, 32,
+    ];
+
+    #[test]
+    fn test_create_with_seed() {
+        assert_eq!(create_with_seed(&[1; 32], "seed", &[2; 32]), Ok(SEEDED));
+        assert_ne!(create_with_seed(&[1; 32], "seeds", &[2; 32]), Ok(SEEDED));
+
+        // The empty seed and the maximum seed length are allowed.
+        assert!(create_with_seed(&[1; 32], "", &[2; 32]).is_ok());
+        assert!(create_with_seed(&[1; 32], "0123456789abcdef0123456789abcdef", &[2; 32]).is_ok());
+    }
+
+    #[test]
+    fn test_verify_address_with_seed() {
+        with_account(SEEDED, |account| {
+            assert_eq!(
+                verify_address_with_seed(account, &[1; 32], "seed", &[2; 32]),
+                Ok(())
+            );
+            assert_eq!(
+                verify_address_with_seed(account, &[1; 32], "other", &[2; 32]),
+                Err(ProgramError::InvalidSeeds)
+            );
+            assert_eq!(
+                verify_address_with_seed(account, &[2; 32], "seed", &[1; 32]),
+                Err(ProgramError::InvalidSeeds)
+            );
+        });
+    }
+
+    #[test]
+    fn test_create_with_seed_invalid() {
+        let mut owner = [1; 32];
+
+        assert_eq!(
+            create_with_seed(&[0; 32], "seed-longer-than-the-maximum-seed-length", &owner),
+            Err(ProgramError::MaxSeedLengthExceeded)
+        );
+
+        owner[32 - PDA_MARKER.len()..].copy_from_slice(PDA_MARKER);
+
+        assert_eq!(
+            create_with_seed(&[0; 32], "seed", &owner),
+            Err(ProgramError::IllegalOwner)
+        );
+    }
+}
@@ -0,0 +1,187 @@
+//! Compile-time implementation of the SHA-256 hash function.
+//!
+//! Programs should use the `sol_sha256` syscall at runtime; this implementation
+//! is only used where the syscall is not available.
+
+/// Initial hash values of SHA-256.
+const H0: [u32; 8] = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
+
+/// Round constants of SHA-256.
+const K: [u32; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+/// Computes the SHA-256 hash of the concatenation of the parts.
+///
+/// This is used to compute event discriminators at compile time and program
+/// addresses off-chain, where the `sol_sha256` syscall is not available.
+pub(crate) const fn sha256(parts: &[&[u8]]) -> [u8; 32] {
+    let mut state = H0;
+    let mut block = [0u8; 64];
+    let mut block_len = 0;
+    let mut total_len = 0u64;
+
+    let mut p = 0;
+
+    while p < parts.len() {
+        let part = parts[p];
+        let mut i = 0;
+
+        while i < part.len() {
+            block[block_len] = part[i];
+            block_len += 1;
+
+            if block_len == 64 {
+                state = compress(state, &block);
+                block_len = 0;
+            }
+            i += 1;
+        }
+
+        total_len += part.len() as u64;
+        p += 1;
+    }
+
+    // Padding: a `1` bit, zeros and the message length in bits.
+    block[block_len] = 0x80;
+    block_len += 1;
+
+    if block_len > 56 {
+        while block_len < 64 {
+            block[block_len] = 0;
+            block_len += 1;
+        }
+        state = compress(state, &block);
+        block_len = 0;
+    }
+
+    while block_len < 56 {
+        block[block_len] = 0;
+        block_len += 1;
+    }
+
+    let length = (total_len * 8).to_be_bytes();
+    let mut i = 0;
+
+    while i < 8 {
+        block[56 + i] = length[i];
+        i += 1;
+    }
+
+    state = compress(state, &block);
+
+    let mut hash = [0u8; 32];
+    let mut i = 0;
+
+    while i < 8 {
+        let bytes = state[i].to_be_bytes();
+        hash[4 * i] = bytes[0];
+        hash[4 * i + 1] = bytes[1];
+        hash[4 * i + 2] = bytes[2];
+        hash[4 * i + 3] = bytes[3];
+        i += 1;
+    }
+
+    hash
+}
+
+/// Processes a 64-byte block of SHA-256.
+const fn compress(state: [u32; 8], block: &[u8; 64]) -> [u32; 8] {
+    let mut w = [0u32; 64];
+    let mut i = 0;
+
+    while i < 16 {
+        w[i] = u32::from_be_bytes([
+            block[4 * i],
+            block[4 * i + 1],
+            block[4 * i + 2],
+            block[4 * i + 3],
+        ]);
+        i += 1;
+    }
+
+    while i < 64 {
+        let s0 = w[i - 15].rotate_right(7) ^ w[i - 15].rotate_right(18) ^ (w[i - 15] >> 3);
+        let s1 = w[i - 2].rotate_right(17) ^ w[i - 2].rotate_right(19) ^ (w[i - 2] >> 10);
+        w[i] = w[i - 16]
+            .wrapping_add(s0)
+            .wrapping_add(w[i - 7])
+            .wrapping_add(s1);
+        i += 1;
+    }
+
+    let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = state;
+    let mut i = 0;
+
+    while i < 64 {
+        let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+        let ch = (e & f) ^ (!e & g);
+        let t1 = h
+            .wrapping_add(s1)
+            .wrapping_add(ch)
+            .wrapping_add(K[i])
+            .wrapping_add(w[i]);
+        let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+        let maj = (a & b) ^ (a & c) ^ (b & c);
+        let t2 = s0.wrapping_add(maj);
+
+        h = g;
+        g = f;
+        f = e;
+        e = d.wrapping_add(t1);
+        d = c;
+        c = b;
+        b = a;
+        a = t1.wrapping_add(t2);
+        i += 1;
+    }
+
+    [
+        state[0].wrapping_add(a),
+        state[1].wrapping_add(b),
+        state[2].wrapping_add(c),
+        state[3].wrapping_add(d),
+        state[4].wrapping_add(e),
+        state[5].wrapping_add(f),
+        state[6].wrapping_add(g),
+        state[7].wrapping_add(h),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sha256() {
+        // `sha256("")`.
+        assert_eq!(
+            sha256(&[]),
+            [
+                0xe3, 0xb0, 0xc4, 0x42, 0x98, 0xfc, 0x1c, 0x14, 0x9a, 0xfb, 0xf4, 0xc8, 0x99, 0x6f,
+                0xb9, 0x24, 0x27, 0xae, 0x41, 0xe4, 0x64, 0x9b, 0x93, 0x4c, 0xa4, 0x95, 0x99, 0x1b,
+                0x78, 0x52, 0xb8, 0x55
+            ]
+        );
+
+        // `sha256("a" * 100)`, spanning multiple blocks.
+        let hash = sha256(&[&[b'a'; 60], &[b'a'; 40]]);
+        assert_eq!(
+            hash,
+            [
+                0x28, 0x16, 0x59, 0x78, 0x88, 0xe4, 0xa0, 0xd3, 0xa3, 0x6b, 0x82, 0xb8, 0x33, 0x16,
+                0xab, 0x32, 0x68, 0x0e, 0xb8, 0xf0, 0x0f, 0x8c, 0xd3, 0xb9, 0x04, 0xd6, 0x81, 0x24,
+                0x6d, 0x28, 0x5a, 0x0e
+            ]
+        );
+    }
+}
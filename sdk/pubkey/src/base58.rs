@@ -0,0 +1,302 @@
+//! Runtime base58 encoding and decoding of `Pubkey`s.
+//!
+//! Both directions are allocation-free and work in `no_std` environments: the
+//! encoded representation is written to a fixed-size buffer of
+//! [`MAX_BASE58_LEN`] bytes.
+
+use core::{
+    fmt,
+    ops::{Deref, DerefMut},
+    str::FromStr,
+};
+
+use pinocchio::pubkey::{Pubkey, PUBKEY_BYTES};
+
+/// Maximum length of the base58 representation of a `Pubkey`.
+pub const MAX_BASE58_LEN: usize = 44;
+
+/// Base58 alphabet used by Solana (the Bitcoin alphabet).
+const ALPHABET: &[u8; 58] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+/// Marker for characters that are not part of the alphabet in `DECODE_TABLE`.
+const INVALID: u8 = 0xff;
+
+/// Lookup table from ASCII characters to base58 digits.
+const DECODE_TABLE: [u8; 128] = {
+    let mut table = [INVALID; 128];
+    let mut i = 0;
+
+    while i < ALPHABET.len() {
+        table[ALPHABET[i] as usize] = i as u8;
+        i += 1;
+    }
+
+    table
+};
+
+/// Errors that can occur when parsing a base58 `Pubkey`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ParsePubkeyError {
+    /// The value does not decode to exactly [`PUBKEY_BYTES`] bytes.
+    WrongSize,
+
+    /// The value contains characters outside the base58 alphabet.
+    Invalid,
+}
+
+impl fmt::Display for ParsePubkeyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParsePubkeyError::WrongSize => f.write_str("String is the wrong size"),
+            ParsePubkeyError::Invalid => f.write_str("Invalid Base58 string"),
+        }
+    }
+}
+
+/// Encodes a `Pubkey` as base58 into the buffer, returning the number of bytes written.
+///
+/// The encoded value is always valid ASCII, so the written part of the buffer can be
+/// safely converted to a `str`.
+pub fn encode(pubkey: &Pubkey, buffer: &mut [u8; MAX_BASE58_LEN]) -> usize {
+    // Base58 digits of the value in little-endian order.
+    let mut digits = [0u8; MAX_BASE58_LEN];
+    let mut length = 0;
+
+    for byte in pubkey {
+        let mut carry = *byte as u32;
+
+        for digit in digits[..length].iter_mut() {
+            carry += (*digit as u32) << 8;
+            *digit = (carry % 58) as u8;
+            carry /= 58;
+        }
+
+        while carry > 0 {
+            digits[length] = (carry % 58) as u8;
+            length += 1;
+            carry /= 58;
+        }
+    }
+
+    // Each leading zero byte is represented by a leading `1`.
+    let zeros = pubkey.iter().take_while(|byte| **byte == 0).count();
+    buffer[..zeros].fill(ALPHABET[0]);
+
+    for (destination, digit) in buffer[zeros..]
+        .iter_mut()
+        .zip(digits[..length].iter().rev())
+    {
+        *destination = ALPHABET[*digit as usize];
+    }
+
+    zeros + length
+}
+
+/// Decodes a base58 `str` into a `Pubkey`.
+///
+/// Unlike [`from_str`](crate::from_str), this function returns an error instead of
+/// panicking when the value is not a valid base58 `Pubkey`.
+pub fn decode(value: &str) -> Result<Pubkey, ParsePubkeyError> {
+    if value.len() > MAX_BASE58_LEN {
+        return Err(ParsePubkeyError::WrongSize);
+    }
+
+    // Bytes of the value in little-endian order.
+    let mut bytes = [0u8; PUBKEY_BYTES];
+    let mut length = 0;
+
+    for character in value.bytes() {
+        let digit = match DECODE_TABLE.get(character as usize) {
+            Some(digit) if *digit != INVALID => *digit,
+            _ => return Err(ParsePubkeyError::Invalid),
+        };
+
+        let mut carry = digit as u32;
+
+        for byte in bytes[..length].iter_mut() {
+            carry += (*byte as u32) * 58;
+            *byte = carry as u8;
+            carry >>= 8;
+        }
+
+        while carry > 0 {
+            if length == PUBKEY_BYTES {
+                return Err(ParsePubkeyError::WrongSize);
+            }
+            bytes[length] = carry as u8;
+            length += 1;
+            carry >>= 8;
+        }
+    }
+
+    // Each leading `1` represents a leading zero byte.
+    let zeros = value.bytes().take_while(|c| *c == ALPHABET[0]).count();
+
+    if zeros + length != PUBKEY_BYTES {
+        return Err(ParsePubkeyError::WrongSize);
+    }
+
+    let mut pubkey = [0u8; PUBKEY_BYTES];
+
+    for (destination, byte) in pubkey[zeros..].iter_mut().zip(bytes[..length].iter().rev()) {
+        *destination = *byte;
+    }
+
+    Ok(pubkey)
+}
+
+/// Wrapper around a `Pubkey` that is displayed and parsed as base58.
+///
+/// # Example
+///
+/// ```
+/// use pinocchio_pubkey::base58::Address;
+///
+/// let address: Address = "11111111111111111111111111111111".parse().unwrap();
+/// assert_eq!(*address, [0; 32]);
+///
+/// let mut buffer = [0; 44];
+/// assert_eq!(address.encode(&mut buffer), "11111111111111111111111111111111");
+/// ```
+#[repr(transparent)]
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Address(pub Pubkey);
+
+impl Address {
+    /// Encodes the address as base58 into the buffer, returning the encoded `str`.
+    #[inline]
+    pub fn encode<'a>(&self, buffer: &'a mut [u8; MAX_BASE58_LEN]) -> &'a str {
+        let length = encode(&self.0, buffer);
+        // SAFETY: The base58 alphabet only contains ASCII characters.
+        unsafe { core::str::from_utf8_unchecked(&buffer[..length]) }
+    }
+}
+
+impl From<Pubkey> for Address {
+    fn from(value: Pubkey) -> Self {
+        Self(value)
+    }
+}
+
+impl From<&Pubkey> for Address {
+    fn from(value: &Pubkey) -> Self {
+        Self(*value)
+    }
+}
+
+impl From<Address> for Pubkey {
+    fn from(value: Address) -> Self {
+        value.0
+    }
+}
+
+impl Deref for Address {
+    type Target = Pubkey;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for Address {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl fmt::Display for Address {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut buffer = [0u8; MAX_BASE58_LEN];
+        f.write_str(self.encode(&mut buffer))
+    }
+}
+
+impl fmt::Debug for Address {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+impl FromStr for Address {
+    type Err = ParsePubkeyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        decode(s).map(Self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Pairs of `Pubkey` values and their base58 representation.
+    const VECTORS: [(Pubkey, &str); 4] = [
+        ([0; 32], "11111111111111111111111111111111"),
+        ([255; 32], "JEKNVnkbo3jma5nREBBJCDoXFVeKkD56V3xKrvRmWxFG"),
+        (
+            [
+                6, 221, 246, 225, 215, 101, 161, 147, 217, 203, 225, 70, 206, 235, 121, 172, 28,
+                180, 133, 237, 95, 91, 55, 145, 58, 140, 245, 133, 126, 255, 0, 169,
+            ],
+            "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA",
+        ),
+        (
+            {
+                let mut pubkey = [0; 32];
+                pubkey[31] = 1;
+                pubkey
+            },
+            "11111111111111111111111111111112",
+        ),
+    ];
+
+    #[test]
+    fn test_encode() {
+        let mut buffer = [0; MAX_BASE58_LEN];
+
+        for (pubkey, expected) in VECTORS {
+            let length = encode(&pubkey, &mut buffer);
+            assert_eq!(&buffer[..length], expected.as_bytes());
+        }
+    }
+
+    #[test]
+    fn test_decode() {
+        for (expected, value) in VECTORS {
+            assert_eq!(decode(value), Ok(expected));
+            #[cfg(feature = "const")]
+            assert_eq!(crate::from_str(value), expected);
+        }
+
+        // Character `0` is not part of the alphabet.
+        assert_eq!(
+            decode("0111111111111111111111111111111"),
+            Err(ParsePubkeyError::Invalid)
+        );
+        assert_eq!(decode("1111"), Err(ParsePubkeyError::WrongSize));
+        assert_eq!(decode(""), Err(ParsePubkeyError::WrongSize));
+        // Decodes to 33 bytes.
+        assert_eq!(
+            decode("111111111111111111111111111111111"),
+            Err(ParsePubkeyError::WrongSize)
+        );
+        assert_eq!(
+            decode("zzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzz"),
+            Err(ParsePubkeyError::WrongSize)
+        );
+    }
+
+    #[test]
+    fn test_address() {
+        let address: Address = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"
+            .parse()
+            .unwrap();
+        assert_eq!(*address, VECTORS[2].0);
+
+        let mut buffer = [0; MAX_BASE58_LEN];
+        assert_eq!(
+            address.encode(&mut buffer),
+            "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"
+        );
+    }
+}
@@ -4,12 +4,16 @@
 #[doc(hidden)]
 // Re-export dependencies used in macros.
 pub mod reexport {
-    pub use pinocchio::pubkey::Pubkey;
+    pub use pinocchio::{event::EVENT_AUTHORITY_SEED, instruction::Seed, pubkey::Pubkey};
 }
 
+pub mod base58;
+
 use core::mem::MaybeUninit;
 #[cfg(feature = "const")]
 pub use five8_const::decode_32_const;
+#[cfg(feature = "const")]
+use pinocchio::pubkey::{is_on_curve, MAX_SEED_LEN};
 use pinocchio::pubkey::{Pubkey, MAX_SEEDS, PDA_MARKER};
 #[cfg(target_os = "solana")]
 use pinocchio::syscalls::sol_sha256;
@@ -153,6 +157,180 @@ pub const fn derive_address_const<const N: usize>(
     }
 }
 
+/// Find a valid [program address][pda] and its canonical bump seed from the given
+/// seeds and program id.
+///
+/// [pda]: https://solana.com/docs/core/pda
+///
+/// This function is intended for use in `const` contexts - i.e., the seeds are known
+/// at compile time and the program id is also a constant. It performs the same search
+/// as [`pinocchio::pubkey::find_program_address`], starting from the bump `255` and
+/// decrementing it until the derived address is a valid (off-curve) program address.
+///
+/// # Panics
+///
+/// Panics if any seed is longer than [`MAX_SEED_LEN`] or if no valid program address
+/// is found, which results in a compile error when used in a `const` context.
+#[cfg(feature = "const")]
+pub const fn find_program_address_const<const N: usize>(
+    seeds: &[&[u8]; N],
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    let mut i = 0;
+
+    while i < N {
+        assert!(
+            seeds[i].len() <= MAX_SEED_LEN,
+            "seed length exceeds MAX_SEED_LEN"
+        );
+        i += 1;
+    }
+
+    let mut bump = u8::MAX;
+
+    loop {
+        let address = derive_address_const(seeds, Some(bump), program_id);
+
+        if !is_on_curve(&address) {
+            return (address, bump);
+        }
+
+        if bump == 0 {
+            panic!("unable to find a valid program address");
+        }
+
+        bump -= 1;
+    }
+}
+
+/// Create an address derived from a base address, a seed and an owner program.
+///
+/// The address is the SHA-256 hash of `base || seed || owner`, the same derivation
+/// used by the System program `*WithSeed` instructions.
+///
+/// This function is intended for use in `const` contexts - i.e., the base, seed and
+/// owner are known at compile time. It is a compile-time constant version of
+/// [`pinocchio::pubkey::create_with_seed`].
+///
+/// # Panics
+///
+/// Panics if the seed is longer than [`MAX_SEED_LEN`] or if the owner ends with the
+/// [`PDA_MARKER`], which results in a compile error when used in a `const` context.
+#[cfg(feature = "const")]
+pub const fn create_with_seed_const(base: &Pubkey, seed: &str, owner: &Pubkey) -> Pubkey {
+    assert!(
+        seed.len() <= MAX_SEED_LEN,
+        "seed length exceeds MAX_SEED_LEN"
+    );
+
+    let mut i = 0;
+    let mut is_pda_owner = true;
+
+    while i < PDA_MARKER.len() {
+        if owner[owner.len() - PDA_MARKER.len() + i] != PDA_MARKER[i] {
+            is_pda_owner = false;
+        }
+        i += 1;
+    }
+
+    assert!(!is_pda_owner, "owner must not end with PDA_MARKER");
+
+    Sha256::new()
+        .update(base)
+        .update(seed.as_bytes())
+        .update(owner)
+        .finalize()
+}
+
+/// Convenience macro to declare a [program address][pda] constant.
+///
+/// [pda]: https://solana.com/docs/core/pda
+///
+/// The canonical bump of the address is computed at compile time using
+/// [`find_program_address_const`]. The macro defines a unit struct with the given
+/// name and the associated constants:
+///
+/// * `ADDRESS`: the program address.
+/// * `BUMP`: the canonical bump seed of the address.
+/// * `SEEDS`: the signer seeds of the address, including the bump seed, which can be
+///   used to create a `Signer`.
+///
+/// Seeds must be constant expressions, e.g., byte string literals or other `Pubkey`
+/// constants.
+///
+/// # Example
+///
+/// ```
+/// use pinocchio::{instruction::Signer, pubkey::Pubkey};
+/// use pinocchio_pubkey::declare_pda;
+///
+/// const PROGRAM_ID: Pubkey = [7; 32];
+///
+/// declare_pda!(VAULT, seeds = [b"vault"], program = PROGRAM_ID);
+///
+/// let address: Pubkey = VAULT::ADDRESS;
+/// let signer = Signer::from(&VAULT::SEEDS);
+/// ```
+#[cfg(feature = "const")]
+#[macro_export]
+macro_rules! declare_pda {
+    ( $name:ident, seeds = [ $($seed:expr),* $(,)? ], program = $program_id:expr $(,)? ) => {
+        #[doc = concat!("The `", stringify!($name), "` program address.")]
+        #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+        pub struct $name;
+
+        impl $name {
+            #[doc = concat!("The canonical bump seed of the `", stringify!($name), "` program address.")]
+            pub const BUMP: u8 = $crate::find_program_address_const(&[$($seed),*], &$program_id).1;
+
+            #[doc = concat!("The `", stringify!($name), "` program address.")]
+            pub const ADDRESS: $crate::reexport::Pubkey =
+                $crate::derive_address_const(&[$($seed),*], Some(Self::BUMP), &$program_id);
+
+            #[doc = concat!("The signer seeds of the `", stringify!($name), "` program address.")]
+            pub const SEEDS: [
+                $crate::reexport::Seed<'static>;
+                <[&str]>::len(&[$(stringify!($seed)),*]) + 1
+            ] = [
+                $($crate::reexport::Seed::new($seed),)*
+                $crate::reexport::Seed::new(&[Self::BUMP]),
+            ];
+        }
+    };
+}
+
+/// Convenience macro to declare the event authority of a program.
+///
+/// The event authority is the program derived address that signs the self-invocations
+/// used to emit events with `Event::emit_cpi`, derived from the seed
+/// [`pinocchio::event::EVENT_AUTHORITY_SEED`]. The macro declares it with
+/// [`declare_pda!`] as `EVENT_AUTHORITY`, so its address, bump and signer seeds are
+/// `EVENT_AUTHORITY::ADDRESS`, `EVENT_AUTHORITY::BUMP` and `EVENT_AUTHORITY::SEEDS`.
+///
+/// # Example
+///
+/// ```
+/// use pinocchio::pubkey::Pubkey;
+/// use pinocchio_pubkey::declare_event_authority;
+///
+/// const PROGRAM_ID: Pubkey = [7; 32];
+///
+/// declare_event_authority!(program = PROGRAM_ID);
+///
+/// assert_eq!(EVENT_AUTHORITY::SEEDS.len(), 2);
+/// ```
+#[cfg(feature = "const")]
+#[macro_export]
+macro_rules! declare_event_authority {
+    ( program = $program_id:expr $(,)? ) => {
+        $crate::declare_pda!(
+            EVENT_AUTHORITY,
+            seeds = [$crate::reexport::EVENT_AUTHORITY_SEED],
+            program = $program_id
+        );
+    };
+}
+
 /// Convenience macro to define a static `Pubkey` value.
 #[cfg(feature = "const")]
 #[macro_export]
@@ -166,13 +344,65 @@ macro_rules! pubkey {
 ///
 /// This macro also defines a helper function to check whether a given pubkey is
 /// equal to the program ID.
+///
+/// # Cluster-specific program IDs
+///
+/// When a program is deployed under different addresses on each cluster, the macro
+/// accepts a list of `cluster = "<address>"` pairs. The program ID is selected by
+/// the cargo feature of the program crate with the same name as the cluster, and
+/// the supported clusters are `mainnet`, `devnet`, `testnet` and `localnet`. It is
+/// a compile error if not exactly one of the listed cluster features is enabled.
+///
+/// ```compile_fail
+/// // None of the cluster features is enabled.
+/// pinocchio_pubkey::declare_id!(
+///     mainnet = "Ping111111111111111111111111111111111111111",
+///     devnet = "Pong111111111111111111111111111111111111111",
+/// );
+/// ```
+///
+/// ```ignore
+/// // Cargo.toml of the program:
+/// //
+/// // [features]
+/// // mainnet = []
+/// // devnet = []
+///
+/// pinocchio_pubkey::declare_id!(
+///     mainnet = "Ping111111111111111111111111111111111111111",
+///     devnet = "Pong111111111111111111111111111111111111111",
+/// );
+/// ```
 #[cfg(feature = "const")]
 #[macro_export]
 macro_rules! declare_id {
-    ( $id:expr ) => {
+    ( $( $cluster:ident = $id:literal ),+ $(,)? ) => {
+        $crate::declare_id!(@select $( $cluster = $id => $crate::__cluster_enabled!($cluster) ),+);
+    };
+    ( @select $( $cluster:ident = $id:literal => $enabled:expr ),+ ) => {
         #[doc = "The constant program ID."]
-        pub const ID: $crate::reexport::Pubkey = $crate::from_str($id);
+        pub const ID: $crate::reexport::Pubkey = {
+            const SELECTED: usize = 0 $( + $enabled as usize )+;
+            assert!(
+                SELECTED == 1,
+                concat!(
+                    "exactly one of the cluster features must be enabled:"
+                    $(, " `", stringify!($cluster), "`")+
+                )
+            );
+
+            let mut id = [0; 32];
+            $(
+                if $enabled {
+                    id = $crate::from_str($id);
+                }
+            )+
+            id
+        };
 
+        $crate::declare_id!(@helpers);
+    };
+    ( @helpers ) => {
         #[doc = "Returns `true` if given pubkey is the program ID."]
         #[inline]
         pub fn check_id(id: &$crate::reexport::Pubkey) -> bool {
@@ -185,6 +415,77 @@ macro_rules! declare_id {
             ID
         }
     };
+    ( $id:expr ) => {
+        #[doc = "The constant program ID."]
+        pub const ID: $crate::reexport::Pubkey = $crate::from_str($id);
+
+        $crate::declare_id!(@helpers);
+    };
+}
+
+/// Returns whether the cargo feature of a cluster is enabled in the crate
+/// invoking the macro.
+///
+/// Unknown clusters are rejected at compile time:
+///
+/// ```compile_fail
+/// pinocchio_pubkey::declare_id!(betanet = "Ping111111111111111111111111111111111111111");
+/// ```
+#[cfg(feature = "const")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __cluster_enabled {
+    ( mainnet ) => {
+        cfg!(feature = "mainnet")
+    };
+    ( devnet ) => {
+        cfg!(feature = "devnet")
+    };
+    ( testnet ) => {
+        cfg!(feature = "testnet")
+    };
+    ( localnet ) => {
+        cfg!(feature = "localnet")
+    };
+    ( $other:ident ) => {
+        compile_error!(concat!(
+            "unknown cluster `",
+            stringify!($other),
+            "`, expected one of `mainnet`, `devnet`, `testnet` or `localnet`"
+        ))
+    };
+}
+
+/// Convenience macro to declare the IDs of several known programs.
+///
+/// Each `name = "<address>"` pair defines a module `name` containing the `ID`
+/// constant and the `check_id()` and `id()` helpers, as generated by
+/// [`declare_id!`].
+///
+/// # Example
+///
+/// ```
+/// pinocchio_pubkey::declare_ids! {
+///     /// SPL Token program.
+///     token = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA",
+///     /// SPL Token 2022 program.
+///     token_2022 = "TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb",
+/// }
+///
+/// assert!(token::check_id(&token::ID));
+/// assert!(!token_2022::check_id(&token::ID));
+/// ```
+#[cfg(feature = "const")]
+#[macro_export]
+macro_rules! declare_ids {
+    ( $( $(#[$meta:meta])* $name:ident = $id:expr ),+ $(,)? ) => {
+        $(
+            $(#[$meta])*
+            pub mod $name {
+                $crate::declare_id!($id);
+            }
+        )+
+    };
 }
 
 /// Create a `Pubkey` from a `&str`.
@@ -193,3 +494,164 @@ macro_rules! declare_id {
 pub const fn from_str(value: &str) -> Pubkey {
     decode_32_const(value)
 }
+
+#[cfg(all(test, feature = "const"))]
+mod tests {
+    use super::*;
+
+    const PROGRAM_ID: Pubkey = [7; 32];
+
+    declare_pda!(VAULT, seeds = [b"vault"], program = PROGRAM_ID);
+
+    declare_pda!(ESCROW, seeds = [b"escrow", &[1; 32]], program = PROGRAM_ID,);
+
+    #[test]
+    fn test_find_program_address_const() {
+        const EMPTY: (Pubkey, u8) = find_program_address_const(&[], &PROGRAM_ID);
+        assert_eq!(
+            EMPTY,
+            (
+                [
+                    163, 1, 142, 166, 38, 222, 236, 13, 130, 94, 184, 109, 33, 252, 41, 85, 157,
+                    139, 106, 13, 14, 168, 162, 9, 201, 242, 246, 196, 245, 98, 75, 166
+                ],
+                254
+            )
+        );
+
+        const B: (Pubkey, u8) = find_program_address_const(&[b"b"], &PROGRAM_ID);
+        assert_eq!(B.1, 255);
+        assert_eq!(B.0, derive_address_const(&[b"b"], Some(255), &PROGRAM_ID));
+    }
+
+    #[test]
+    fn test_declare_pda() {
+        assert_eq!(
+            VAULT::ADDRESS,
+            [
+                63, 204, 154, 156, 44, 128, 245, 147, 167, 68, 46, 138, 245, 247, 248, 127, 186,
+                116, 98, 91, 132, 36, 242, 186, 228, 156, 78, 10, 164, 22, 194, 236
+            ]
+        );
+        assert_eq!(VAULT::BUMP, 254);
+        assert_eq!(VAULT::SEEDS.len(), 2);
+        assert_eq!(&*VAULT::SEEDS[0], b"vault");
+        assert_eq!(&*VAULT::SEEDS[1], &[254]);
+
+        assert_eq!(
+            ESCROW::ADDRESS,
+            [
+                221, 109, 129, 227, 190, 244, 94, 121, 3, 86, 181, 250, 210, 65, 134, 60, 185, 90,
+                126, 18, 152, 81, 27, 232, 242, 114, 186, 37, 210, 226, 235, 30
+            ]
+        );
+        assert_eq!(ESCROW::BUMP, 253);
+        assert_eq!(ESCROW::SEEDS.len(), 3);
+        assert_eq!(&*ESCROW::SEEDS[1], &[1; 32]);
+        assert_eq!(&*ESCROW::SEEDS[2], &[253]);
+    }
+
+    declare_ids! {
+        /// System program.
+        system = "11111111111111111111111111111111",
+        token = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA",
+    }
+
+    declare_event_authority!(program = PROGRAM_ID);
+
+    #[test]
+    fn test_declare_event_authority() {
+        let (address, bump) = find_program_address_const(&[b"__event_authority"], &PROGRAM_ID);
+
+        assert_eq!(EVENT_AUTHORITY::ADDRESS, address);
+        assert_eq!(EVENT_AUTHORITY::BUMP, bump);
+        assert_eq!(&*EVENT_AUTHORITY::SEEDS[0], b"__event_authority");
+    }
+
+    #[test]
+    fn test_declare_ids() {
+        assert_eq!(system::ID, [0; 32]);
+        assert_eq!(system::id(), [0; 32]);
+        assert_eq!(
+            token::ID,
+            [
+                6, 221, 246, 225, 215, 101, 161, 147, 217, 203, 225, 70, 206, 235, 121, 172, 28,
+                180, 133, 237, 95, 91, 55, 145, 58, 140, 245, 133, 126, 255, 0, 169
+            ]
+        );
+        assert_eq!(token::id(), token::ID);
+        assert!(token::check_id(&token::ID));
+        assert!(!system::check_id(&token::ID));
+        assert!(!token::check_id(&system::ID));
+    }
+
+    mod program {
+        crate::declare_id!("Ping111111111111111111111111111111111111111");
+    }
+
+    mod devnet_program {
+        crate::declare_id!(
+            @select
+            mainnet = "Ping111111111111111111111111111111111111111" => false,
+            devnet = "Pong111111111111111111111111111111111111111" => true
+        );
+    }
+
+    mod mainnet_program {
+        crate::declare_id!(
+            @select
+            mainnet = "Ping111111111111111111111111111111111111111" => true,
+            devnet = "Pong111111111111111111111111111111111111111" => false
+        );
+    }
+
+    #[test]
+    fn test_declare_id() {
+        const PING: Pubkey = [
+            5, 210, 6, 235, 46, 253, 102, 159, 31, 71, 190, 7, 187, 195, 98, 80, 228, 203, 27, 227,
+            161, 42, 34, 234, 132, 113, 40, 128, 0, 0, 0, 0,
+        ];
+        const PONG: Pubkey = [
+            5, 215, 174, 56, 36, 8, 149, 109, 197, 21, 68, 157, 204, 65, 18, 48, 55, 213, 176, 158,
+            8, 156, 227, 248, 82, 120, 138, 128, 0, 0, 0, 0,
+        ];
+
+        assert_eq!(program::ID, PING);
+        assert_eq!(program::id(), PING);
+        assert!(program::check_id(&PING));
+        assert!(!program::check_id(&PONG));
+
+        // The address of the enabled cluster is selected.
+        assert_eq!(mainnet_program::ID, PING);
+        assert_eq!(mainnet_program::id(), PING);
+        assert!(mainnet_program::check_id(&PING));
+        assert_eq!(devnet_program::ID, PONG);
+        assert_eq!(devnet_program::id(), PONG);
+        assert!(devnet_program::check_id(&PONG));
+        assert!(!devnet_program::check_id(&PING));
+    }
+
+    #[test]
+    fn test_create_with_seed_const() {
+        const ADDRESS: Pubkey = create_with_seed_const(&[1; 32], "seed", &[2; 32]);
+        assert_eq!(
+            ADDRESS,
+            [
+                190, 17, 200, 199, 90, 126, 37, 199, 227, 150, 44, 113, 236, 142, 120, 63, 9, 253,
+                29, 91, 203, 57, 29, 232, 77, 141, 143, 38, 40, 3, 70, 32
+            ]
+        );
+
+        // The const and runtime implementations derive the same addresses.
+        for (base, seed, owner) in [
+            ([1; 32], "seed", [2; 32]),
+            ([0; 32], "", [0; 32]),
+            ([255; 32], "0123456789abcdef0123456789abcdef", [3; 32]),
+        ] {
+            assert_eq!(
+                pinocchio::pubkey::create_with_seed(&base, seed, &owner),
+                Ok(create_with_seed_const(&base, seed, &owner))
+            );
+        }
+    }
+}